@@ -0,0 +1,53 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+//! Run with `cargo +nightly fuzz run xml_to_json`. The first byte selects the configuration,
+//! the rest is the document.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use xml_to_json_rs::{DuplicateAttributes, InlineConflict, KeyRename, NullPolicy, XmlToJson};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((config, xml)) = data.split_first() else {
+        return;
+    };
+    let parser = match config % 4 {
+        0 => XmlToJson::default(),
+        1 => XmlToJson::default()
+            .with_root()
+            .with_text_name("")
+            .with_attribute_prefix("")
+            .with_key_rename(KeyRename::prefix("", "x"))
+            .with_flatten_text_arrays()
+            .with_null_policy(NullPolicy::Never),
+        2 => XmlToJson::default()
+            .with_dtd()
+            .with_html_entities()
+            .with_unknown_entities_as_text()
+            .with_duplicate_attributes(DuplicateAttributes::MakeArray)
+            .with_input_sanitizing(true)
+            .with_canonicalization()
+            .with_declaration(),
+        _ => XmlToJson::default()
+            .with_max_convert_depth(2)
+            .with_max_output_bytes(64)
+            .with_case_insensitive_keys()
+            .with_sibling_index()
+            .with_source_spans()
+            .with_inline_simple_children(InlineConflict::Mixed)
+            .with_null_policy(NullPolicy::XsiNil),
+    };
+    if let Ok(value) = parser.xml_to_json_bytes(xml) {
+        let _ = parser.json_to_xml_pretty(&value, "", 2);
+        let _ = parser.json_to_xml_pretty(&value, "r", 0);
+    }
+    if let Ok(xml) = std::str::from_utf8(xml) {
+        let _ = parser.xml_to_json_partial(xml);
+        let _ = parser.xml_to_json_with_quick_xml(xml);
+        let _ = parser.xml_to_events(xml, |_| {});
+    }
+});
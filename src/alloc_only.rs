@@ -0,0 +1,146 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+//! basic converter for targets without `std`, it only needs `alloc`.
+//! The conversion is the same as the default conversion with `std`.
+
+use crate::depth::{nesting_depth, DEFAULT_MAX_NESTING_DEPTH};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use core::fmt;
+use serde_json::{Map, Value};
+
+/// The XML could not be parsed
+#[derive(Debug)]
+pub enum ParseError {
+    /// the document isn't well-formed
+    Xml(roxmltree::Error),
+    /// the elements are nested deeper than the limit, which is the default limit of `std`
+    TooDeep(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Xml(err) => err.fmt(f),
+            ParseError::TooDeep(depth) => write!(
+                f,
+                "nesting depth {depth} exceeds the limit of {}",
+                DEFAULT_MAX_NESTING_DEPTH
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// XML to JSON converter with the basic options, enable the `std` feature for all others
+pub struct XmlToJson {
+    with_root: bool,
+    text_name: String,
+    attribute_prefix: String,
+}
+
+impl Default for XmlToJson {
+    fn default() -> Self {
+        XmlToJson {
+            with_root: false,
+            text_name: "#text".to_string(),
+            attribute_prefix: "@".to_string(),
+        }
+    }
+}
+
+impl XmlToJson {
+    /// adds the root element name to the JSON output
+    pub fn with_root(mut self) -> Self {
+        self.with_root = true;
+        self
+    }
+
+    /// the key of element text, default is `#text`
+    pub fn with_text_name(mut self, text_name: &str) -> Self {
+        self.text_name = text_name.to_string();
+        self
+    }
+
+    /// the prefix of attribute keys, default is `@`
+    pub fn with_attribute_prefix(mut self, attribute_prefix: &str) -> Self {
+        self.attribute_prefix = attribute_prefix.to_string();
+        self
+    }
+
+    /// Parse XML string and return serde_json Value
+    pub fn xml_to_json(&self, xml: &str) -> Result<Value, ParseError> {
+        let depth = nesting_depth(xml);
+        if depth > DEFAULT_MAX_NESTING_DEPTH {
+            return Err(ParseError::TooDeep(depth));
+        }
+        let doc = roxmltree::Document::parse(xml).map_err(ParseError::Xml)?;
+        let root = doc.root_element();
+        let value = self.parse_node(&root).unwrap_or(Value::Null);
+        if self.with_root {
+            let mut map = Map::new();
+            map.insert(root.tag_name().name().to_string(), value);
+            Ok(Value::Object(map))
+        } else {
+            Ok(value)
+        }
+    }
+
+    fn parse_node(&self, node: &roxmltree::Node) -> Option<Value> {
+        let mut elements = Map::new();
+        if let Some(text) = node.text().map(str::trim).filter(|text| !text.is_empty()) {
+            elements.insert(self.text_name.clone(), Value::String(text.to_string()));
+        }
+        for attr in node.attributes() {
+            let key = [self.attribute_prefix.as_str(), attr.name()].concat();
+            elements.insert(key, Value::String(attr.value().trim().to_string()));
+        }
+        for child in node.children().filter(roxmltree::Node::is_element) {
+            let Some(child_val) = self.parse_node(&child) else {
+                continue;
+            };
+            let name = child.tag_name().name();
+            if let Some(found) = elements.get_mut(name) {
+                if let Some(array) = found.as_array_mut() {
+                    array.push(child_val);
+                } else {
+                    *found = Value::Array(vec![found.take(), child_val]);
+                }
+            } else {
+                elements.insert(name.to_string(), child_val);
+            }
+        }
+        (!elements.is_empty()).then_some(Value::Object(elements))
+    }
+}
+
+#[test]
+fn test_alloc_only() {
+    use serde_json::json;
+
+    let xml = "<a id=\"1\"><b> x </b><b>y</b><c/></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_root()
+            .with_text_name("value")
+            .with_attribute_prefix("_")
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "a": { "_id": "1", "b": [{ "value": "x" }, { "value": "y" }] } })
+    );
+    assert!(matches!(
+        XmlToJson::default().xml_to_json("<a><b></a>"),
+        Err(ParseError::Xml(_))
+    ));
+    let nested = ["<a>".repeat(300), "</a>".repeat(300)].concat();
+    let err = XmlToJson::default().xml_to_json(&nested).unwrap_err();
+    assert!(matches!(err, ParseError::TooDeep(300)));
+    assert_eq!(
+        err.to_string(),
+        "nesting depth 300 exceeds the limit of 256"
+    );
+}
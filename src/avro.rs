@@ -0,0 +1,183 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::{json, Map, Value};
+use std::hash::{BuildHasher, RandomState};
+use std::io::Write;
+
+/// returns a valid Avro name for `key`, like `_href` for `@href` or `c_d` for `c.d`
+fn avro_name(key: &str) -> String {
+    let mut name: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// returns the Avro type of a flattened value, or `None` for null
+fn avro_type(val: &Value) -> Option<&'static str> {
+    match val {
+        Value::Null => None,
+        Value::Bool(_) => Some("boolean"),
+        Value::Number(num) if num.is_i64() => Some("long"),
+        Value::Number(_) => Some("double"),
+        _ => Some("string"),
+    }
+}
+
+/// write `val` with zig-zag and variable length encoding
+fn write_long(out: &mut Vec<u8>, val: i64) {
+    let mut zigzag = ((val << 1) ^ (val >> 63)) as u64;
+    while zigzag >= 0x80 {
+        out.push((zigzag as u8) | 0x80);
+        zigzag >>= 7;
+    }
+    out.push(zigzag as u8);
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(out, bytes.len() as i64);
+    out.extend_from_slice(bytes);
+}
+
+/// The fields of all records with their Avro type
+struct AvroFields {
+    fields: Vec<(String, &'static str)>,
+}
+
+impl AvroFields {
+    /// collect the fields of all records, keys with values of different types are strings
+    fn new(records: &[Map<String, Value>]) -> Self {
+        let mut fields: Vec<(String, Option<&'static str>)> = Vec::new();
+        for (key, val) in records.iter().flatten() {
+            let new_kind = avro_type(val);
+            match fields.iter_mut().find(|(name, _)| name == key) {
+                None => fields.push((key.clone(), new_kind)),
+                Some((_, kind)) if kind.is_none() => *kind = new_kind,
+                Some((_, kind)) if new_kind.is_some() && *kind != new_kind => {
+                    *kind = Some("string")
+                }
+                Some(_) => {}
+            }
+        }
+        let fields = fields
+            .into_iter()
+            .map(|(key, kind)| (key, kind.unwrap_or("string")))
+            .collect();
+        AvroFields { fields }
+    }
+
+    /// a record schema named `name`, all fields are nullable as they may be missing
+    fn schema(&self, name: &str) -> Value {
+        let fields: Vec<Value> = self
+            .fields
+            .iter()
+            .map(|(key, kind)| json!({ "name": avro_name(key), "type": ["null", kind], "default": null }))
+            .collect();
+        json!({ "type": "record", "name": avro_name(name), "fields": fields })
+    }
+
+    /// append the binary encoding of `record`
+    fn encode(&self, record: &Map<String, Value>, out: &mut Vec<u8>) {
+        for (key, kind) in &self.fields {
+            let val = record.get(key).unwrap_or(&Value::Null);
+            if val.is_null() {
+                write_long(out, 0);
+                continue;
+            }
+            write_long(out, 1);
+            match (*kind, val) {
+                ("boolean", Value::Bool(val)) => out.push(*val as u8),
+                ("long", Value::Number(num)) => write_long(out, num.as_i64().unwrap_or_default()),
+                ("double", Value::Number(num)) => {
+                    out.extend(num.as_f64().unwrap_or_default().to_le_bytes())
+                }
+                (_, Value::String(text)) => write_bytes(out, text.as_bytes()),
+                (_, other) => write_bytes(out, other.to_string().as_bytes()),
+            }
+        }
+    }
+}
+
+impl XmlToJson {
+    /// Returns the Avro schema of the records matched by the query `record_path`, flattened
+    /// like `to_records`. The types are inferred from the converted values, so numbers and
+    /// booleans need type overrides or a number mode. Keys are changed to valid Avro names.
+    pub fn avro_schema(
+        &self,
+        xml: &str,
+        record_path: &str,
+        name: &str,
+    ) -> Result<Value, Box<dyn std::error::Error>> {
+        let records = self.to_records(xml, record_path)?;
+        Ok(AvroFields::new(&records).schema(name))
+    }
+
+    /// Write the records matched by the query `record_path` as Avro object container file,
+    /// with the schema of `avro_schema`, uncompressed and in a single block
+    pub fn xml_to_avro(
+        &self,
+        xml: &str,
+        record_path: &str,
+        name: &str,
+        mut writer: impl Write,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let records = self.to_records(xml, record_path)?;
+        let fields = AvroFields::new(&records);
+        let schema = fields.schema(name).to_string();
+        let hasher = RandomState::new();
+        let sync: Vec<u8> = [hasher.hash_one(&schema), hasher.hash_one(records.len())]
+            .iter()
+            .flat_map(|hash| hash.to_le_bytes())
+            .collect();
+        let mut out = b"Obj\x01".to_vec();
+        write_long(&mut out, 2);
+        write_bytes(&mut out, b"avro.schema");
+        write_bytes(&mut out, schema.as_bytes());
+        write_bytes(&mut out, b"avro.codec");
+        write_bytes(&mut out, b"null");
+        write_long(&mut out, 0);
+        out.extend(&sync);
+        let mut block = Vec::new();
+        for record in &records {
+            fields.encode(record, &mut block);
+        }
+        write_long(&mut out, records.len() as i64);
+        write_bytes(&mut out, &block);
+        out.extend(&sync);
+        writer.write_all(&out)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_xml_to_avro() {
+    use crate::JsonType;
+    use std::collections::HashMap;
+
+    let xml = r#"<a><b id="1"><c>x</c></b><b id="2" ok="true"/></a>"#;
+    let mut overrides = HashMap::new();
+    overrides.insert("/a/b/@id".to_string(), JsonType::Number);
+    overrides.insert("/a/b/@ok".to_string(), JsonType::Bool);
+    let parser = XmlToJson::default().with_type_overrides(overrides);
+    assert_eq!(
+        parser.avro_schema(xml, "$.b[*]", "b").unwrap(),
+        json!({ "type": "record", "name": "b", "fields": [
+            { "name": "_id", "type": ["null", "long"], "default": null },
+            { "name": "c", "type": ["null", "string"], "default": null },
+            { "name": "_ok", "type": ["null", "boolean"], "default": null },
+        ] })
+    );
+    let mut avro = Vec::new();
+    parser.xml_to_avro(xml, "$.b[*]", "b", &mut avro).unwrap();
+    assert!(avro.starts_with(b"Obj\x01"));
+    // 2 records in a block of 11 bytes, with union index and value of each field
+    let block = [4, 22, 2, 2, 2, 2, b'x', 0, 2, 4, 0, 2, 1];
+    assert_eq!(avro[avro.len() - 16 - block.len()..avro.len() - 16], block);
+}
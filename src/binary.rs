@@ -0,0 +1,75 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+/// Encoding of binary payload elements, see `XmlToJson::with_binary_elements`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryMode {
+    /// standard base64 with padding, whitespace and line breaks are removed
+    Base64,
+    /// hexadecimal, whitespace is removed and digits are converted to lowercase
+    Hex,
+}
+
+/// the error message for invalid binary content, without the content itself,
+/// which can be large. `offset` is the position of the first invalid character.
+fn invalid_content(encoding: &str, len: usize, offset: Option<usize>) -> String {
+    match offset {
+        Some(offset) => format!("invalid {encoding} character at offset {offset} of {len}"),
+        None => format!("invalid {encoding} length {len}"),
+    }
+}
+
+impl BinaryMode {
+    /// validate `text` and return the normalized encoding together with the decoded byte length
+    pub(crate) fn normalize(&self, text: &str) -> Result<(String, usize), String> {
+        let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        match self {
+            BinaryMode::Base64 => {
+                let data = compact.trim_end_matches('=');
+                let padding = compact.len() - data.len();
+                let invalid = data
+                    .bytes()
+                    .position(|b| !(b.is_ascii_alphanumeric() || b == b'+' || b == b'/'));
+                if let Some(offset) = invalid {
+                    return Err(invalid_content("base64", compact.len(), Some(offset)));
+                }
+                if !compact.len().is_multiple_of(4) || padding > 2 {
+                    return Err(invalid_content("base64", compact.len(), None));
+                }
+                let len = compact.len() / 4 * 3 - padding;
+                Ok((compact, len))
+            }
+            BinaryMode::Hex => {
+                if let Some(offset) = compact.bytes().position(|b| !b.is_ascii_hexdigit()) {
+                    return Err(invalid_content("hex", compact.len(), Some(offset)));
+                }
+                if !compact.len().is_multiple_of(2) {
+                    return Err(invalid_content("hex", compact.len(), None));
+                }
+                let len = compact.len() / 2;
+                Ok((compact.to_ascii_lowercase(), len))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_binary_mode_normalize() {
+    assert_eq!(
+        BinaryMode::Base64.normalize(" aGVs\n bG8= ").unwrap(),
+        ("aGVsbG8=".to_string(), 5)
+    );
+    assert_eq!(
+        BinaryMode::Hex.normalize("DE AD be ef").unwrap(),
+        ("deadbeef".to_string(), 4)
+    );
+    assert!(BinaryMode::Base64.normalize("aGVsbG8").is_err());
+    assert!(BinaryMode::Base64.normalize("a=Vs").is_err());
+    assert!(BinaryMode::Hex.normalize("abc").is_err());
+    assert_eq!(
+        BinaryMode::Hex.normalize(&"ab".repeat(1000).replace("abab", "abxb")),
+        Err("invalid hex character at offset 2 of 2000".to_string())
+    );
+}
@@ -0,0 +1,170 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+
+/// A parsed XML document, see `XmlToJson::parse_document`
+pub struct XmlDocument<'input>(roxmltree::Document<'input>);
+
+/// A JSON value that borrows names and texts from an `XmlDocument` instead of copying them,
+/// see `XmlToJson::to_borrowed`. Object entries keep the document order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BorrowedValue<'doc> {
+    Null,
+    String(Cow<'doc, str>),
+    Array(Vec<BorrowedValue<'doc>>),
+    Object(Vec<(Cow<'doc, str>, BorrowedValue<'doc>)>),
+}
+
+impl<'doc> BorrowedValue<'doc> {
+    /// the value of `key` if this is an object
+    pub fn get(&self, key: &str) -> Option<&BorrowedValue<'doc>> {
+        match self {
+            BorrowedValue::Object(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, val)| val),
+            _ => None,
+        }
+    }
+
+    /// the text if this is a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BorrowedValue::String(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl From<BorrowedValue<'_>> for Value {
+    fn from(value: BorrowedValue<'_>) -> Self {
+        match value {
+            BorrowedValue::Null => Value::Null,
+            BorrowedValue::String(text) => Value::String(text.into_owned()),
+            BorrowedValue::Array(array) => array.into_iter().map(Value::from).collect(),
+            BorrowedValue::Object(entries) => Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, val)| (key.into_owned(), Value::from(val)))
+                    .collect::<Map<_, _>>(),
+            ),
+        }
+    }
+}
+
+impl XmlToJson {
+    /// parse `xml` once for `to_borrowed`
+    pub fn parse_document<'input>(
+        &self,
+        xml: &'input str,
+    ) -> Result<XmlDocument<'input>, Box<dyn std::error::Error>> {
+        self.check_nesting_depth(xml)?;
+        Ok(XmlDocument(roxmltree::Document::parse_with_options(
+            xml,
+            self.parsing_options(),
+        )?))
+    }
+
+    /// Convert `doc` without copying element names, attribute values and texts, for
+    /// inspecting documents that are discarded afterwards. Only the root, text name,
+    /// attribute prefix and attribute whitespace options are applied. Attribute keys are
+    /// copied unless the attribute prefix is empty, attribute values only if normalizing
+    /// them changes more than the surrounding whitespace. Convert the result with
+    /// `Value::from` if needed.
+    pub fn to_borrowed<'doc>(&'doc self, doc: &'doc XmlDocument) -> BorrowedValue<'doc> {
+        let root = doc.0.root_element();
+        let value = self.borrowed_node(root).unwrap_or(BorrowedValue::Null);
+        match self.with_root {
+            true => BorrowedValue::Object(vec![(Cow::Borrowed(root.tag_name().name()), value)]),
+            false => value,
+        }
+    }
+
+    fn borrowed_node<'doc>(
+        &'doc self,
+        node: roxmltree::Node<'doc, '_>,
+    ) -> Option<BorrowedValue<'doc>> {
+        let mut entries = Vec::new();
+        if let Some(text) = node.text() {
+            let text_name = self.text_name_for(node.tag_name().name());
+            entries.push((
+                Cow::Borrowed(text_name),
+                BorrowedValue::String(Cow::Borrowed(text.trim())),
+            ));
+        }
+        for attr in node.attributes() {
+            let key = match self.attribute_prefix.is_empty() {
+                true => Cow::Borrowed(attr.name()),
+                false => Cow::Owned([&self.attribute_prefix, attr.name()].concat()),
+            };
+            let val = match self.source_attribute_value(&node, &attr) {
+                Cow::Borrowed(value) => Cow::Borrowed(self.attribute_value(value)),
+                Cow::Owned(value) => Cow::Owned(self.attribute_value(&value).to_string()),
+            };
+            entries.push((key, BorrowedValue::String(val)));
+        }
+        for child in node.children().filter(|child| child.is_element()) {
+            let Some(child_val) = self.borrowed_node(child) else {
+                continue;
+            };
+            let name = child.tag_name().name();
+            match entries.iter_mut().find(|(key, _)| key == name) {
+                Some((_, BorrowedValue::Array(array))) => array.push(child_val),
+                Some((_, found)) => {
+                    let first = std::mem::replace(found, BorrowedValue::Null);
+                    *found = BorrowedValue::Array(vec![first, child_val]);
+                }
+                None => entries.push((Cow::Borrowed(name), child_val)),
+            }
+        }
+        (!entries.is_empty()).then_some(BorrowedValue::Object(entries))
+    }
+}
+
+#[test]
+fn test_to_borrowed() {
+    let xml = r#"<a id="1"><b>x</b><b>y</b><c lang="en">z</c><d/></a>"#;
+    let parser = XmlToJson::default();
+    let doc = parser.parse_document(xml).unwrap();
+    let borrowed = parser.to_borrowed(&doc);
+    let text = borrowed.get("c").and_then(|c| c.get("#text")).unwrap();
+    assert!(matches!(text, BorrowedValue::String(Cow::Borrowed("z"))));
+    assert_eq!(Value::from(borrowed), parser.xml_to_json(xml).unwrap());
+    let parser = parser.with_root().with_attribute_prefix("");
+    let doc = parser.parse_document(xml).unwrap();
+    assert_eq!(
+        Value::from(parser.to_borrowed(&doc)),
+        parser.xml_to_json(xml).unwrap()
+    );
+    let docs = [
+        "<a/>",
+        "<a><!-- c --></a>",
+        "<a x=\" 2 \"><b y=\"\n 3&#10;\t\"/></a>",
+    ];
+    for parser in [
+        XmlToJson::default(),
+        XmlToJson::default().with_root(),
+        XmlToJson::default().with_preserve_attribute_whitespace(),
+    ] {
+        for xml in docs {
+            let doc = parser.parse_document(xml).unwrap();
+            assert_eq!(
+                Value::from(parser.to_borrowed(&doc)),
+                parser.xml_to_json(xml).unwrap(),
+                "{xml}"
+            );
+        }
+    }
+    let parser = XmlToJson::default();
+    let doc = parser.parse_document("<a x=\" 2 \"/>").unwrap();
+    let borrowed = parser.to_borrowed(&doc);
+    assert!(matches!(
+        borrowed.get("@x"),
+        Some(BorrowedValue::String(Cow::Borrowed("2")))
+    ));
+}
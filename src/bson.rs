@@ -0,0 +1,148 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{JsonSink, XmlToJson};
+use serde_json::Number;
+
+/// Encodes the converted document as BSON, see `XmlToJson::xml_to_bson`.
+/// A BSON document needs an object at the top level.
+#[derive(Default)]
+pub struct BsonSink {
+    buf: Vec<u8>,
+    /// offset of the length of each open document, and the next index of open arrays
+    stack: Vec<(usize, Option<usize>)>,
+    key: String,
+    error: Option<String>,
+}
+
+impl BsonSink {
+    /// write the element type and name, returns false at the top level
+    fn element(&mut self, element_type: u8) -> bool {
+        let Some((_, index)) = self.stack.last_mut() else {
+            return false;
+        };
+        let name = match index {
+            Some(index) => {
+                *index += 1;
+                (*index - 1).to_string()
+            }
+            None => std::mem::take(&mut self.key),
+        };
+        if name.contains('\0') {
+            self.error = Some(format!("invalid key '{name}'"));
+        }
+        self.buf.push(element_type);
+        self.buf.extend(name.as_bytes());
+        self.buf.push(0);
+        true
+    }
+
+    fn scalar(&mut self, element_type: u8, bytes: &[u8]) {
+        if self.element(element_type) {
+            self.buf.extend(bytes);
+        } else {
+            self.error = Some("BSON documents need an object at the top level".into());
+        }
+    }
+
+    fn begin_document(&mut self, element_type: u8, array: bool) {
+        self.element(element_type);
+        self.stack.push((self.buf.len(), array.then_some(0)));
+        self.buf.extend([0; 4]);
+    }
+
+    fn end_document(&mut self) {
+        if let Some((start, _)) = self.stack.pop() {
+            self.buf.push(0);
+            let len = (self.buf.len() - start) as i32;
+            self.buf[start..start + 4].copy_from_slice(&len.to_le_bytes());
+        }
+    }
+}
+
+impl JsonSink for BsonSink {
+    type Output = Result<Vec<u8>, String>;
+    fn null(&mut self) {
+        self.scalar(0x0a, &[]);
+    }
+    fn bool(&mut self, val: bool) {
+        self.scalar(0x08, &[val as u8]);
+    }
+    fn number(&mut self, val: &Number) {
+        match val.as_i64() {
+            Some(num) => match i32::try_from(num) {
+                Ok(num) => self.scalar(0x10, &num.to_le_bytes()),
+                Err(_) => self.scalar(0x12, &num.to_le_bytes()),
+            },
+            None => self.scalar(0x01, &val.as_f64().unwrap_or_default().to_le_bytes()),
+        }
+    }
+    fn string(&mut self, val: &str) {
+        let bytes = [&(val.len() as i32 + 1).to_le_bytes(), val.as_bytes(), &[0]].concat();
+        self.scalar(0x02, &bytes);
+    }
+    fn begin_object(&mut self, _len: usize) {
+        self.begin_document(0x03, false);
+    }
+    fn key(&mut self, key: &str) {
+        self.key = key.into();
+    }
+    fn end_object(&mut self) {
+        self.end_document();
+    }
+    fn begin_array(&mut self, _len: usize) {
+        if self.stack.is_empty() {
+            self.error = Some("BSON documents need an object at the top level".into());
+        }
+        self.begin_document(0x04, true);
+    }
+    fn end_array(&mut self) {
+        self.end_document();
+    }
+    fn finish(self) -> Result<Vec<u8>, String> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.buf),
+        }
+    }
+}
+
+impl XmlToJson {
+    /// Parse XML string and return it encoded as BSON document. The document is encoded while
+    /// traversing the XML, without a `Value` in between, unless an option of `xml_to_sink`
+    /// needs it.
+    pub fn xml_to_bson(&self, xml: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Ok(self.xml_to_sink(xml, BsonSink::default())??)
+    }
+}
+
+#[test]
+fn test_xml_to_bson() {
+    let parser = XmlToJson::default().with_flatten_text_arrays();
+    assert_eq!(
+        parser.xml_to_bson("<a><b>x</b><b>y</b></a>").unwrap(),
+        [
+            &[31, 0, 0, 0, 0x04, b'b', 0, 23, 0, 0, 0][..],
+            &[0x02, b'0', 0, 2, 0, 0, 0, b'x', 0],
+            &[0x02, b'1', 0, 2, 0, 0, 0, b'y', 0, 0, 0],
+        ]
+        .concat()
+    );
+    assert!(parser
+        .with_null_policy(crate::NullPolicy::Null)
+        .xml_to_bson("<a/>")
+        .is_err());
+    let parser = XmlToJson::default();
+    #[cfg(feature = "roxmltree")]
+    assert!(parser.streams_to_sink());
+    assert_eq!(
+        parser.xml_to_bson("<a x=\"1\"><b/></a>").unwrap(),
+        [
+            &[15, 0, 0, 0][..],
+            &[0x02, b'@', b'x', 0, 2, 0, 0, 0, b'1', 0, 0],
+        ]
+        .concat()
+    );
+}
@@ -0,0 +1,72 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+
+/// Decides how an element is converted, returned by the callback of
+/// `XmlToJson::with_element_callback`
+#[derive(Clone, Debug, PartialEq)]
+pub enum ElementAction {
+    /// convert the element as configured
+    Continue,
+    /// drop the element including all children
+    Skip,
+    /// convert the element with another name
+    Rename(String),
+    /// convert the element into an array, also if it isn't repeated
+    ForceArray,
+}
+
+impl XmlToJson {
+    /// call `callback` with the path, name and attributes of each element before it is converted.
+    /// The returned `ElementAction` can skip or rename the element or force an array, for cases
+    /// that the static options don't cover. The root element can only be renamed.
+    pub fn with_element_callback(
+        mut self,
+        callback: impl Fn(&str, &str, &[(&str, &str)]) -> ElementAction + Send + Sync + 'static,
+    ) -> Self {
+        self.element_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// the action for `node`, whose path was already entered in `ctx`
+    #[cfg(feature = "roxmltree")]
+    pub(crate) fn element_action(
+        &self,
+        node: &roxmltree::Node,
+        ctx: &crate::Context,
+    ) -> ElementAction {
+        let Some(callback) = &self.element_callback else {
+            return ElementAction::Continue;
+        };
+        let attributes: Vec<_> = node
+            .attributes()
+            .map(|attr| (attr.name(), attr.value()))
+            .collect();
+        callback(&ctx.path, node.tag_name().name(), &attributes)
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_element_callback() {
+    use serde_json::json;
+
+    let xml = r#"<a><secret>x</secret><b kind="list">1</b><c>2</c><c>3</c></a>"#;
+    let parser = XmlToJson::default().with_element_callback(|path, name, attributes| {
+        match (path, name, attributes) {
+            (_, "secret", _) => ElementAction::Skip,
+            (_, _, [("kind", "list")]) => ElementAction::ForceArray,
+            ("/a/c", _, _) => ElementAction::Rename("d".into()),
+            ("/a", _, _) => ElementAction::Rename("root".into()),
+            _ => ElementAction::Continue,
+        }
+    });
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({ "b": [{ "@kind": "list", "#text": "1" }], "d": [{ "#text": "2" }, { "#text": "3" }] })
+    );
+    assert!(parser.with_root().xml_to_json(xml).unwrap()["root"].is_object());
+}
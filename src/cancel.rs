@@ -0,0 +1,96 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{Context, XmlToJson};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Cancels running conversions from another thread, see `XmlToJson::with_cancellation`
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Returned if a conversion was cancelled or exceeded its deadline
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cancelled {
+    /// XML path of the element where the conversion stopped
+    pub path: String,
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "conversion cancelled at '{}'", self.path)
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+impl XmlToJson {
+    /// abort conversions with a `Cancelled` error when `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// abort conversions with a `Cancelled` error when they take longer than `timeout`
+    pub fn with_deadline(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(timeout);
+        self
+    }
+
+    /// the instant when a conversion that starts now has to be finished
+    pub(crate) fn deadline_from_now(&self) -> Option<Instant> {
+        self.deadline.map(|timeout| Instant::now() + timeout)
+    }
+
+    pub(crate) fn check_cancelled(&self, ctx: &Context) -> Result<(), Cancelled> {
+        let cancelled = self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled);
+        if cancelled
+            || ctx
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(Cancelled {
+                path: ctx.path.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_cancellation() {
+    let token = CancellationToken::new();
+    let parser = XmlToJson::default().with_cancellation(token.clone());
+    assert!(parser.xml_to_json("<a><b/></a>").is_ok());
+    token.cancel();
+    let err = parser.xml_to_json("<a><b/></a>").unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<Cancelled>(),
+        Some(&Cancelled { path: "/a".into() })
+    );
+
+    let parser = XmlToJson::default().with_deadline(Duration::ZERO);
+    let err = parser.xml_to_json("<a><b/></a>").unwrap_err();
+    assert!(err.is::<Cancelled>());
+}
@@ -0,0 +1,60 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{Context, XmlToJson};
+use serde_json::Value;
+
+impl XmlToJson {
+    /// Find all elements named `name` at any depth and convert each of them. Returns the
+    /// path of each element, like `/catalog/book/price`, together with its value.
+    /// Matches inside of other matches are returned as well.
+    pub fn collect_elements(
+        &self,
+        xml: &str,
+        name: &str,
+    ) -> Result<Vec<(String, Value)>, Box<dyn std::error::Error>> {
+        let mut ctx = Context {
+            deadline: self.deadline_from_now(),
+            ..Default::default()
+        };
+        let xml = self.preprocess(xml, &mut ctx)?;
+        let doc = roxmltree::Document::parse_with_options(&xml, self.parsing_options())?;
+        let mut found = Vec::new();
+        for node in doc
+            .root_element()
+            .descendants()
+            .filter(|node| node.is_element() && self.element_name(node) == name)
+        {
+            let mut ancestors: Vec<_> = node
+                .ancestors()
+                .skip(1)
+                .filter(|ancestor| ancestor.is_element())
+                .map(|ancestor| self.element_name(&ancestor))
+                .collect();
+            ancestors.reverse();
+            ctx.path = ancestors.iter().map(|name| ["/", name].concat()).collect();
+            let value = self.parse_root(&node, &mut ctx)?.unwrap_or(Value::Null);
+            found.push(([&ctx.path, "/", name].concat(), self.rename_keys(value)));
+        }
+        Ok(found)
+    }
+}
+
+#[test]
+fn test_collect_elements() {
+    use serde_json::json;
+
+    let xml = "<catalog><book><price>10</price></book><box><book><price cur=\"EUR\">5</price></book></box></catalog>";
+    assert_eq!(
+        XmlToJson::default().collect_elements(xml, "price").unwrap(),
+        [
+            ("/catalog/book/price".to_string(), json!({ "#text": "10" })),
+            (
+                "/catalog/box/book/price".to_string(),
+                json!({ "@cur": "EUR", "#text": "5" })
+            ),
+        ]
+    );
+}
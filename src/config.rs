@@ -0,0 +1,412 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{
+    ArrayMode, AttributeNormalization, ConversionProfile, InlineConflict, JsonType, KeyRename,
+    NamespaceDeclarations, NamespaceMode, NullPolicy, PromotionConflict, SiblingDeduplication,
+    XmlToJson,
+};
+use serde_json::Value;
+use std::fmt;
+
+/// All conflicting options found by `XmlToJson::build`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigError {
+    pub conflicts: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration: {}", self.conflicts.join("; "))
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn as_str<'a>(key: &str, val: &'a Value) -> Result<&'a str, String> {
+    val.as_str()
+        .ok_or_else(|| format!("config '{key}' must be a string"))
+}
+
+fn as_bool(key: &str, val: &Value) -> Result<bool, String> {
+    val.as_bool()
+        .ok_or_else(|| format!("config '{key}' must be a boolean"))
+}
+
+fn as_usize(key: &str, val: &Value) -> Result<usize, String> {
+    val.as_u64()
+        .map(|num| num as usize)
+        .ok_or_else(|| format!("config '{key}' must be a positive integer"))
+}
+
+fn as_str_list<'a>(key: &str, val: &'a Value) -> Result<Vec<&'a str>, String> {
+    val.as_array()
+        .ok_or_else(|| format!("config '{key}' must be an array"))?
+        .iter()
+        .map(|item| as_str(key, item))
+        .collect()
+}
+
+fn parse_rename(val: &Value) -> Result<KeyRename, String> {
+    let to = as_str("renames.to", val.get("to").unwrap_or(&Value::Null))?;
+    if let Some(from) = val.get("key") {
+        Ok(KeyRename::key(as_str("renames.key", from)?, to))
+    } else if let Some(from) = val.get("prefix") {
+        Ok(KeyRename::prefix(as_str("renames.prefix", from)?, to))
+    } else if let Some(path) = val.get("path") {
+        Ok(KeyRename::path(as_str("renames.path", path)?, to))
+    } else {
+        Err("config 'renames' entries need a 'key', 'prefix' or 'path'".into())
+    }
+}
+
+impl XmlToJson {
+    /// Build a converter from a declarative JSON config, for example
+    /// `{"root": true, "text_name": "value", "plural_names": {"item": "items"}}`.
+    ///
+    /// Supported keys, unknown keys are rejected:
+    /// - `root`, `flatten_text_arrays`, `strict`, `html_entities`, `canonicalize`,
+    ///   `declaration`, `dtd`, `case_insensitive_keys`, `original_names`, `source_spans`,
+    ///   `preserve_attribute_whitespace`, `preserve_attribute_order`, `directives`: booleans
+    /// - `text_name`, `attribute_prefix`, `compact_attributes_key`, `flatten`: strings
+    /// - `max_convert_depth`, `max_nesting_depth`, `max_output_bytes`,
+    ///   `max_attributes_per_element`, `max_name_length`: positive integers
+    /// - `plural_names`: map of singular to plural name
+    /// - `text_names`: map of element name to text name
+    /// - `inherited_attributes`, `compact_attribute_elements`, `datetime_normalization`,
+    ///   `promote_attributes`, `presence_attributes`: lists of names
+    /// - `strip_namespaces`: list of namespace URIs
+    /// - `array_paths`: list of XML paths
+    /// - `renames`: list of `{"key"|"prefix"|"path": …, "to": …}`
+    /// - `type_overrides`: map of path to `string`, `number` or `bool`
+    /// - `null_policy`: `skip`, `xsi_nil`, `null` or `never`
+    /// - `namespace_mode`: `strip`, `prefix` or `expanded`
+    /// - `namespace_declarations`: `keep`, `drop` or `collect`
+    /// - `inline_simple_children`: `mixed` or `keep_objects`
+    /// - `attribute_normalization`: `spec` or `raw`
+    /// - `sibling_deduplication`: `count` or `reference`
+    /// - `promotion_conflict`: `keep_prefix`, `prefer_child` or `prefer_attribute`
+    /// - `array_mode`: `auto`, `always_wrap_children` or `error_on_unexpected_repeat`
+    /// - `profile`: `v1` or `v2`, applied before all other keys
+    pub fn from_config(config: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let config: Value = serde_json::from_str(config)?;
+        let entries = config.as_object().ok_or("config must be a JSON object")?;
+        let mut parser = XmlToJson::default();
+        if let Some(val) = entries.get("profile") {
+            parser = parser.with_profile(match as_str("profile", val)? {
+                "v1" => ConversionProfile::V1,
+                "v2" => ConversionProfile::V2,
+                other => return Err(format!("unknown profile '{other}'").into()),
+            });
+        }
+        for (key, val) in entries {
+            let key = key.as_str();
+            parser = match key {
+                "profile" => parser,
+                "root" => XmlToJson {
+                    with_root: as_bool(key, val)?,
+                    ..parser
+                },
+                "text_name" => parser.with_text_name(as_str(key, val)?),
+                "flatten" => parser.with_flatten(as_str(key, val)?),
+                "attribute_prefix" => parser.with_attribute_prefix(as_str(key, val)?),
+                "flatten_text_arrays" => XmlToJson {
+                    flatten_text_arrays: as_bool(key, val)?,
+                    ..parser
+                },
+                "plural_names" => {
+                    let names = val
+                        .as_object()
+                        .ok_or("config 'plural_names' must be an object")?
+                        .iter()
+                        .map(|(singular, plural)| Ok((singular.as_str(), as_str(key, plural)?)))
+                        .collect::<Result<Vec<_>, String>>()?;
+                    parser.with_plural_names(&names)
+                }
+                "text_names" => {
+                    let names = val
+                        .as_object()
+                        .ok_or("config 'text_names' must be an object")?
+                        .iter()
+                        .map(|(element, text_name)| Ok((element.as_str(), as_str(key, text_name)?)))
+                        .collect::<Result<Vec<_>, String>>()?;
+                    parser.with_text_name_for(&names)
+                }
+                "strip_namespaces" => parser.with_strip_namespaces(&as_str_list(key, val)?),
+                "inherited_attributes" => parser.with_inherited_attributes(&as_str_list(key, val)?),
+                "renames" => {
+                    let renames = val.as_array().ok_or("config 'renames' must be an array")?;
+                    renames.iter().try_fold(parser, |parser, rename| {
+                        Ok::<_, String>(parser.with_key_rename(parse_rename(rename)?))
+                    })?
+                }
+                "compact_attribute_elements" => {
+                    parser.with_compact_attribute_elements(&as_str_list(key, val)?)
+                }
+                "datetime_normalization" => {
+                    parser.with_datetime_normalization(&as_str_list(key, val)?)
+                }
+                "compact_attributes_key" => parser.with_compact_attributes_key(as_str(key, val)?),
+                "null_policy" => parser.with_null_policy(match as_str(key, val)? {
+                    "skip" => NullPolicy::Skip,
+                    "xsi_nil" => NullPolicy::XsiNil,
+                    "null" => NullPolicy::Null,
+                    "never" => NullPolicy::Never,
+                    other => return Err(format!("unknown null_policy '{other}'").into()),
+                }),
+                "array_paths" => parser.with_array_paths(&as_str_list(key, val)?),
+                "array_mode" => parser.with_array_mode(match as_str(key, val)? {
+                    "auto" => ArrayMode::Auto,
+                    "always_wrap_children" => ArrayMode::AlwaysWrapChildren,
+                    "error_on_unexpected_repeat" => ArrayMode::ErrorOnUnexpectedRepeat,
+                    other => return Err(format!("unknown array_mode '{other}'").into()),
+                }),
+                "presence_attributes" => parser.with_presence_attributes(&as_str_list(key, val)?),
+                "promote_attributes" => parser.with_promote_attributes(&as_str_list(key, val)?),
+                "promotion_conflict" => parser.with_promotion_conflict(match as_str(key, val)? {
+                    "keep_prefix" => PromotionConflict::KeepPrefix,
+                    "prefer_child" => PromotionConflict::PreferChild,
+                    "prefer_attribute" => PromotionConflict::PreferAttribute,
+                    other => return Err(format!("unknown promotion_conflict '{other}'").into()),
+                }),
+                "sibling_deduplication" => {
+                    parser.with_sibling_deduplication(match as_str(key, val)? {
+                        "count" => SiblingDeduplication::Count,
+                        "reference" => SiblingDeduplication::Reference,
+                        other => {
+                            return Err(format!("unknown sibling_deduplication '{other}'").into())
+                        }
+                    })
+                }
+                "attribute_normalization" => {
+                    parser.with_attribute_normalization(match as_str(key, val)? {
+                        "spec" => AttributeNormalization::Spec,
+                        "raw" => AttributeNormalization::Raw,
+                        other => {
+                            return Err(format!("unknown attribute_normalization '{other}'").into())
+                        }
+                    })
+                }
+                "namespace_mode" => parser.with_namespace_mode(match as_str(key, val)? {
+                    "strip" => NamespaceMode::Strip,
+                    "prefix" => NamespaceMode::Prefix,
+                    "expanded" => NamespaceMode::Expanded,
+                    other => return Err(format!("unknown namespace_mode '{other}'").into()),
+                }),
+                "namespace_declarations" => {
+                    parser.with_namespace_declarations(match as_str(key, val)? {
+                        "keep" => NamespaceDeclarations::Keep,
+                        "drop" => NamespaceDeclarations::Drop,
+                        "collect" => NamespaceDeclarations::Collect,
+                        other => {
+                            return Err(format!("unknown namespace_declarations '{other}'").into())
+                        }
+                    })
+                }
+                "inline_simple_children" => {
+                    parser.with_inline_simple_children(match as_str(key, val)? {
+                        "mixed" => InlineConflict::Mixed,
+                        "keep_objects" => InlineConflict::KeepObjects,
+                        other => {
+                            return Err(format!("unknown inline_simple_children '{other}'").into())
+                        }
+                    })
+                }
+                "type_overrides" => {
+                    let overrides = val
+                        .as_object()
+                        .ok_or("config 'type_overrides' must be an object")?
+                        .iter()
+                        .map(|(path, name)| {
+                            let name = as_str(key, name)?;
+                            let json_type = JsonType::from_name(name)
+                                .ok_or_else(|| format!("unknown type '{name}'"))?;
+                            Ok((path.clone(), json_type))
+                        })
+                        .collect::<Result<_, String>>()?;
+                    parser.with_type_overrides(overrides)
+                }
+                "max_convert_depth" => parser.with_max_convert_depth(as_usize(key, val)?),
+                "max_output_bytes" => parser.with_max_output_bytes(as_usize(key, val)?),
+                "max_attributes_per_element" => {
+                    parser.with_max_attributes_per_element(as_usize(key, val)?)
+                }
+                "max_name_length" => parser.with_max_name_length(as_usize(key, val)?),
+                "max_nesting_depth" => parser.with_max_nesting_depth(as_usize(key, val)?),
+                "strict" => XmlToJson {
+                    strict: as_bool(key, val)?,
+                    ..parser
+                },
+                "html_entities" => XmlToJson {
+                    html_entities: as_bool(key, val)?,
+                    ..parser
+                },
+                "declaration" => XmlToJson {
+                    with_declaration: as_bool(key, val)?,
+                    ..parser
+                },
+                "canonicalize" => XmlToJson {
+                    canonicalize: as_bool(key, val)?,
+                    ..parser
+                },
+                "dtd" => XmlToJson {
+                    allow_dtd: as_bool(key, val)?,
+                    ..parser
+                },
+                "preserve_attribute_order" => XmlToJson {
+                    preserve_attribute_order: as_bool(key, val)?,
+                    ..parser
+                },
+                "case_insensitive_keys" => XmlToJson {
+                    case_insensitive_keys: as_bool(key, val)?,
+                    ..parser
+                },
+                "original_names" => XmlToJson {
+                    original_names: as_bool(key, val)?,
+                    ..parser
+                },
+                "preserve_attribute_whitespace" => XmlToJson {
+                    preserve_attribute_whitespace: as_bool(key, val)?,
+                    ..parser
+                },
+                "directives" => XmlToJson {
+                    directives: as_bool(key, val)?,
+                    ..parser
+                },
+                "source_spans" => XmlToJson {
+                    source_spans: as_bool(key, val)?,
+                    ..parser
+                },
+                other => return Err(format!("unknown config key '{other}'").into()),
+            };
+        }
+        Ok(parser.build()?)
+    }
+
+    /// Validate the configuration and return it, or all conflicting options
+    pub fn build(self) -> Result<Self, ConfigError> {
+        let mut conflicts = Vec::new();
+        if self.text_name.is_empty() {
+            conflicts.push("the text name must not be empty".to_string());
+        }
+        if !self.attribute_prefix.is_empty() && self.text_name.starts_with(&self.attribute_prefix) {
+            conflicts.push(format!(
+                "text name '{}' starts with attribute prefix '{}'",
+                self.text_name, self.attribute_prefix
+            ));
+        }
+        if self.attribute_prefix.is_empty() && self.compact_attribute_elements.is_some() {
+            conflicts.push("compact attribute elements need an attribute prefix".into());
+        }
+        if self.compact_attributes_key.is_some() && self.compact_attribute_elements.is_none() {
+            conflicts.push("compact attributes key without compact attribute elements".into());
+        }
+        if self.original_names && !self.case_insensitive_keys {
+            conflicts.push("original names without case insensitive keys".into());
+        }
+        if self.binary_length_only && self.binary_elements.is_empty() {
+            conflicts.push("binary length only without binary elements".into());
+        }
+        if self.entity_resolver.is_some() && !self.allow_dtd {
+            conflicts.push("entity resolver without dtd".into());
+        }
+        if self.deadline.is_some_and(|deadline| deadline.is_zero()) {
+            conflicts.push("the deadline must not be zero".into());
+        }
+        // strict promises a lossless conversion, but these options drop information unreported
+        if self.strict {
+            let lossy = [
+                (!self.key_renames.is_empty(), "key renames"),
+                (self.flatten_text_arrays, "flattened text arrays"),
+                (self.case_insensitive_keys, "case insensitive keys"),
+                (self.max_convert_depth.is_some(), "max convert depth"),
+                (self.max_output_bytes.is_some(), "max output bytes"),
+            ];
+            for (_, option) in lossy.iter().filter(|(enabled, _)| *enabled) {
+                conflicts.push(format!("strict conversion with lossy option '{option}'"));
+            }
+        }
+        #[cfg(not(feature = "roxmltree"))]
+        {
+            let unsupported = [
+                (self.strict || self.loss_callback.is_some(), "loss reports"),
+                (self.include_resolver.is_some(), "includes"),
+                (self.element_callback.is_some(), "element callbacks"),
+                (!self.array_paths.is_empty(), "array paths"),
+                (self.preserve_attribute_order, "attribute order"),
+                (
+                    !self.inherited_attributes.is_empty(),
+                    "inherited attributes",
+                ),
+                (!self.binary_elements.is_empty(), "binary elements"),
+                (self.max_convert_depth.is_some(), "max convert depth"),
+                (self.max_output_bytes.is_some(), "max output bytes"),
+                (self.canonicalize, "canonicalization"),
+                (self.duplicate_attributes.is_some(), "duplicate attributes"),
+                (self.with_declaration, "declaration"),
+                (self.source_spans, "source spans"),
+                (!self.stripped_namespaces.is_empty(), "stripped namespaces"),
+                (!self.key_filters.is_empty(), "key filters"),
+                (self.directives, "directives"),
+                (
+                    self.namespace_mode == NamespaceMode::Expanded,
+                    "expanded namespaces",
+                ),
+                (
+                    self.null_policy == NullPolicy::XsiNil,
+                    "xsi:nil null policy",
+                ),
+            ];
+            for (_, option) in unsupported.iter().filter(|(enabled, _)| *enabled) {
+                conflicts.push(format!("'{option}' requires the roxmltree backend"));
+            }
+        }
+        match conflicts.is_empty() {
+            true => Ok(self),
+            false => Err(ConfigError { conflicts }),
+        }
+    }
+}
+
+#[test]
+fn test_from_config() {
+    use serde_json::json;
+
+    let config = r#"{
+        "root": true,
+        "text_name": "value",
+        "plural_names": { "item": "items" },
+        "renames": [{ "prefix": "@", "to": "_" }],
+        "null_policy": "null"
+    }"#;
+    let parser = XmlToJson::from_config(config).unwrap();
+    assert_eq!(
+        parser
+            .xml_to_json("<a id=\"1\"><item>x</item><b/></a>")
+            .unwrap(),
+        json!({ "a": { "_id": "1", "items": [{ "value": "x" }], "b": null } })
+    );
+    assert!(XmlToJson::from_config(r#"{ "unknown": true }"#).is_err());
+    assert!(XmlToJson::from_config(r#"{ "root": "yes" }"#).is_err());
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_build() {
+    assert!(XmlToJson::default().with_root().build().is_ok());
+    let result = XmlToJson::default()
+        .with_attribute_prefix("#")
+        .with_flatten_text_arrays()
+        .strict()
+        .build();
+    assert_eq!(
+        result.err().unwrap().conflicts,
+        [
+            "text name '#text' starts with attribute prefix '#'",
+            "strict conversion with lossy option 'flattened text arrays'",
+        ]
+    );
+    assert!(XmlToJson::from_config(r#"{ "original_names": true }"#).is_err());
+}
@@ -0,0 +1,244 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+//! detection and normalization of ISO 8601, RFC 822 and Unix epoch timestamps
+
+use crate::XmlToJson;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// takes exactly `len` digits from the start of `text`
+fn digits(text: &str, len: usize) -> Option<(i64, &str)> {
+    let digits = text.get(..len)?;
+    if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    Some((digits.parse().ok()?, &text[len..]))
+}
+
+/// parses a number of 1 to `max_len` digits
+fn number(text: &str, max_len: usize) -> Option<i64> {
+    if text.is_empty() || text.len() > max_len || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// days since 1970-01-01, None if the date doesn't exist
+fn days_from_civil(year: i64, month: i64, day: i64) -> Option<i64> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let days = era * 146097 + yoe * 365 + yoe / 4 - yoe / 100 + doy - 719468;
+    // days that overflow into the next month, like April 31, are rejected
+    (civil_from_days(days) == (year, month, day)).then_some(days)
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+fn seconds_of_day(hour: i64, minute: i64, second: i64) -> Option<i64> {
+    // 60 is a leap second
+    (hour < 24 && minute < 60 && second <= 60).then_some(hour * 3600 + minute * 60 + second)
+}
+
+/// the offset of a numeric time zone like `Z`, `+01:00` or `-0500` in seconds
+fn zone_offset(zone: &str) -> Option<i64> {
+    let (sign, zone) = match zone {
+        "" | "Z" | "z" => return Some(0),
+        _ => match (zone.strip_prefix('+'), zone.strip_prefix('-')) {
+            (Some(zone), _) => (1, zone),
+            (_, Some(zone)) => (-1, zone),
+            _ => return None,
+        },
+    };
+    let (hours, rest) = digits(zone, 2)?;
+    let rest = rest.strip_prefix(':').unwrap_or(rest);
+    let minutes = match rest {
+        "" => 0,
+        _ => number(rest, 2).filter(|_| rest.len() == 2)?,
+    };
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// `2024-01-02`, `2024-01-02T03:04:05.123+01:00` and variants, returns the seconds since
+/// the epoch and the fractional digits
+fn parse_iso(text: &str) -> Option<(i64, &str)> {
+    let (year, rest) = digits(text, 4)?;
+    let (month, rest) = digits(rest.strip_prefix('-')?, 2)?;
+    let (day, rest) = digits(rest.strip_prefix('-')?, 2)?;
+    let days = days_from_civil(year, month, day)?;
+    if rest.is_empty() {
+        return Some((days * 86400, ""));
+    }
+    let rest = rest.strip_prefix(['T', 't', ' '])?;
+    let (hour, rest) = digits(rest, 2)?;
+    let (minute, mut rest) = digits(rest.strip_prefix(':')?, 2)?;
+    let mut second = 0;
+    if let Some(seconds) = rest.strip_prefix(':') {
+        (second, rest) = digits(seconds, 2)?;
+    }
+    let mut fraction = "";
+    if let Some(fractional) = rest.strip_prefix(['.', ',']) {
+        let len = fractional.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return None;
+        }
+        (fraction, rest) = fractional.split_at(len);
+    }
+    let time = seconds_of_day(hour, minute, second)?;
+    Some((days * 86400 + time - zone_offset(rest)?, fraction))
+}
+
+/// `Tue, 02 Jan 2024 03:04:05 GMT` as used by RSS, the weekday and seconds are optional
+fn parse_rfc822(text: &str) -> Option<i64> {
+    let text = match text.split_once(',') {
+        Some((weekday, rest)) if WEEKDAYS.contains(&weekday.trim()) => rest,
+        Some(_) => return None,
+        None => text,
+    };
+    let mut parts = text.split_ascii_whitespace();
+    let day = number(parts.next()?, 2)?;
+    let month_name = parts.next()?;
+    let month = MONTHS
+        .iter()
+        .position(|month| month.eq_ignore_ascii_case(month_name))? as i64
+        + 1;
+    let year = parts.next()?;
+    let year = match (year.len(), number(year, 4)?) {
+        (2, year) if year < 50 => year + 2000,
+        (2, year) => year + 1900,
+        (4, year) => year,
+        _ => return None,
+    };
+    let mut time = parts.next()?.split(':');
+    let hour = number(time.next()?, 2)?;
+    let minute = number(time.next()?, 2)?;
+    let second = time.next().map_or(Some(0), |second| number(second, 2))?;
+    if time.next().is_some() {
+        return None;
+    }
+    let zone = parts.next().unwrap_or("GMT");
+    if parts.next().is_some() {
+        return None;
+    }
+    let offset = match zone {
+        "GMT" | "UT" | "UTC" => 0,
+        "EDT" => -4 * 3600,
+        "EST" | "CDT" => -5 * 3600,
+        "CST" | "MDT" => -6 * 3600,
+        "MST" | "PDT" => -7 * 3600,
+        "PST" => -8 * 3600,
+        _ => zone_offset(zone).filter(|_| zone.len() == 5)?,
+    };
+    let days = days_from_civil(year, month, day)?;
+    Some(days * 86400 + seconds_of_day(hour, minute, second)? - offset)
+}
+
+/// Unix timestamps with 10 digits in seconds or 13 digits in milliseconds
+fn parse_epoch(text: &str) -> Option<(i64, String)> {
+    match text.len() {
+        10 => Some((number(text, 10)?, String::new())),
+        13 => {
+            let millis = number(text, 13)?;
+            let fraction = match millis % 1000 {
+                0 => String::new(),
+                millis => format!("{millis:03}"),
+            };
+            Some((millis / 1000, fraction))
+        }
+        _ => None,
+    }
+}
+
+/// returns `text` as RFC 3339 timestamp in UTC, like `2024-01-02T03:04:05Z`, if it is
+/// a supported date or time
+pub(crate) fn normalize_datetime(text: &str) -> Option<String> {
+    let (seconds, fraction) = match parse_iso(text) {
+        Some((seconds, fraction)) => (seconds, fraction.to_string()),
+        None => match parse_rfc822(text) {
+            Some(seconds) => (seconds, String::new()),
+            None => parse_epoch(text)?,
+        },
+    };
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+    if !(0..=9999).contains(&year) {
+        return None;
+    }
+    let time = seconds.rem_euclid(86400);
+    let (hour, minute, second) = (time / 3600, time % 3600 / 60, time % 60);
+    let dot = if fraction.is_empty() { "" } else { "." };
+    Some(format!(
+        "{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}{dot}{fraction}Z"
+    ))
+}
+
+impl XmlToJson {
+    /// convert dates and timestamps to RFC 3339 in UTC, e.g. `Tue, 02 Jan 2024 03:04:05 +0100`
+    /// to `2024-01-02T02:04:05Z`. Detected are ISO 8601, RFC 822 as used by RSS, and Unix
+    /// timestamps with 10 or 13 digits. Only the text and attributes of elements and attributes
+    /// named like `names` are converted, all values if it is empty. Other values are kept.
+    pub fn with_datetime_normalization(mut self, names: &[&str]) -> Self {
+        self.datetime_names = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// returns the normalized timestamp of the value at `path`, see `with_datetime_normalization`
+    pub(crate) fn normalized_datetime(&self, path: &str, text: &str) -> Option<String> {
+        let names = self.datetime_names.as_ref()?;
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let name = name
+            .strip_prefix(self.attribute_prefix.as_str())
+            .unwrap_or(name);
+        if names.is_empty() || names.iter().any(|datetime_name| datetime_name == name) {
+            normalize_datetime(text)
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn test_datetime_normalization() {
+    use serde_json::json;
+
+    let xml = r#"<rss><item updated="1704164645"><pubDate>Tue, 02 Jan 2024 03:04:05 +0100</pubDate>
+        <date>2024-01-02</date><time>2024-01-02T03:04:05.25-05:30</time><edt>2 Jan 24 03:04 EDT</edt>
+        <ms>1704164645123</ms><bad>2024-02-30</bad><id>1704164645</id></item></rss>"#;
+    let parser = XmlToJson::default()
+        .with_flatten_text_arrays()
+        .with_inline_simple_children(crate::InlineConflict::Mixed)
+        .with_datetime_normalization(&["updated", "pubDate", "date", "time", "edt", "ms", "bad"]);
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap()["item"],
+        json!({
+            "@updated": "2024-01-02T03:04:05Z",
+            "pubDate": "2024-01-02T02:04:05Z",
+            "date": "2024-01-02T00:00:00Z",
+            "time": "2024-01-02T08:34:05.25Z",
+            "edt": "2024-01-02T07:04:00Z",
+            "ms": "2024-01-02T03:04:05.123Z",
+            "bad": "2024-02-30",
+            "id": "1704164645",
+        })
+    );
+}
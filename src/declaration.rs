@@ -0,0 +1,47 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use serde_json::{Map, Value};
+
+/// returns the XML declaration `<?xml ...?>` at the start of `xml` without the delimiters
+pub(crate) fn xml_declaration(xml: &str) -> Option<&str> {
+    let rest = xml.trim_start_matches('\u{feff}').strip_prefix("<?xml")?;
+    Some(&rest[..rest.find("?>")?])
+}
+
+/// returns the value of a pseudo attribute like `encoding` inside of an XML declaration
+pub(crate) fn pseudo_attribute<'a>(declaration: &'a str, name: &str) -> Option<&'a str> {
+    let rest = &declaration[declaration.find(name)? + name.len()..];
+    let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &rest[1..];
+    Some(&rest[..rest.find(quote)?])
+}
+
+/// returns the name of the DOCTYPE declaration in front of the root element
+fn doctype_name(prolog: &str) -> Option<&str> {
+    let rest = prolog[prolog.find("<!DOCTYPE")? + "<!DOCTYPE".len()..].trim_start();
+    let end = rest
+        .find(|c: char| c.is_whitespace() || c == '[' || c == '>')
+        .unwrap_or(rest.len());
+    Some(&rest[..end])
+}
+
+/// collect XML declaration and DOCTYPE name of a document, `prolog` is the input in front
+/// of the root element
+pub(crate) fn declaration_value(prolog: &str) -> Value {
+    let mut map = Map::new();
+    if let Some(declaration) = xml_declaration(prolog) {
+        for name in ["version", "encoding", "standalone"] {
+            if let Some(val) = pseudo_attribute(declaration, name) {
+                map.insert(name.into(), Value::String(val.into()));
+            }
+        }
+    }
+    if let Some(name) = doctype_name(prolog) {
+        map.insert("doctype".into(), Value::String(name.into()));
+    }
+    Value::Object(map)
+}
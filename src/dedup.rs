@@ -0,0 +1,99 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Defines how identical repeated siblings are collapsed, see
+/// `XmlToJson::with_sibling_deduplication`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SiblingDeduplication {
+    /// keep the first of identical siblings and add the number of occurrences as `#count`
+    Count,
+    /// replace repeated siblings by `{"#duplicate": index}`, the array index of the first one
+    Reference,
+}
+
+impl XmlToJson {
+    /// collapse repeated sibling elements with exactly the same content, attributes and
+    /// children, e.g. padding in machine generated XML. Siblings that were converted to plain
+    /// text or null are kept.
+    pub fn with_sibling_deduplication(mut self, deduplication: SiblingDeduplication) -> Self {
+        self.sibling_deduplication = Some(deduplication);
+        self
+    }
+
+    /// collapse identical objects in the arrays of repeated children
+    pub(crate) fn deduplicate_siblings(&self, elements: &mut Map<String, Value>) {
+        let Some(deduplication) = self.sibling_deduplication else {
+            return;
+        };
+        for array in elements.values_mut().filter_map(Value::as_array_mut) {
+            // serialized siblings and the index of their first occurrence in `deduplicated`
+            let mut seen: HashMap<String, usize> = HashMap::new();
+            let mut counts = Vec::new();
+            let mut deduplicated = Vec::with_capacity(array.len());
+            for (index, value) in std::mem::take(array).into_iter().enumerate() {
+                if !value.is_object() {
+                    deduplicated.push(value);
+                    counts.push(1);
+                    continue;
+                }
+                let serialized = value.to_string();
+                match seen.get(&serialized) {
+                    Some(&first) if deduplication == SiblingDeduplication::Count => {
+                        counts[first] += 1;
+                    }
+                    Some(&first) => {
+                        let mut reference = Map::new();
+                        reference.insert("#duplicate".into(), first.into());
+                        deduplicated.push(Value::Object(reference));
+                        counts.push(1);
+                    }
+                    None => {
+                        let first = match deduplication {
+                            SiblingDeduplication::Count => deduplicated.len(),
+                            SiblingDeduplication::Reference => index,
+                        };
+                        seen.insert(serialized, first);
+                        deduplicated.push(value);
+                        counts.push(1);
+                    }
+                }
+            }
+            for (value, count) in deduplicated.iter_mut().zip(counts) {
+                if let (Some(object), 2..) = (value.as_object_mut(), count) {
+                    object.insert("#count".into(), count.into());
+                }
+            }
+            *array = deduplicated;
+        }
+    }
+}
+
+#[test]
+fn test_sibling_deduplication() {
+    use serde_json::json;
+
+    let xml = r#"<a><p v="0"/><p v="0"/><p v="1"/><p v="0"/><q>x</q><q>x</q></a>"#;
+    assert_eq!(
+        XmlToJson::default()
+            .with_sibling_deduplication(SiblingDeduplication::Count)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({
+            "p": [{ "@v": "0", "#count": 3 }, { "@v": "1" }],
+            "q": [{ "#text": "x", "#count": 2 }],
+        })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_sibling_deduplication(SiblingDeduplication::Reference)
+            .xml_to_json(xml)
+            .unwrap()["p"],
+        json!([{ "@v": "0" }, { "#duplicate": 0 }, { "@v": "1" }, { "#duplicate": 0 }])
+    );
+}
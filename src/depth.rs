@@ -0,0 +1,129 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+//! nesting depth check before parsing. roxmltree and the converter recurse per element level,
+//! so deeply nested documents would overflow the stack instead of returning an error.
+
+/// default of `XmlToJson::with_max_nesting_depth`, low enough for a 2 MiB thread stack
+/// in debug builds
+pub(crate) const DEFAULT_MAX_NESTING_DEPTH: usize = 256;
+
+/// roxmltree expands entity references up to this depth
+const MAX_ENTITY_DEPTH: usize = 10;
+
+/// Returns an upper bound of the element nesting depth of `xml`, which is never lower than
+/// the depth roxmltree sees. Markup in an internal DTD subset is counted as if it was
+/// referenced at every entity level. The result is only exact for well-formed documents.
+pub(crate) fn nesting_depth(xml: &str) -> usize {
+    let bytes = xml.as_bytes();
+    let (mut depth, mut max) = (0usize, 0usize);
+    // markup of entities that can be referenced at any level
+    let mut entity_depth = 0;
+    let mut pos = 0;
+    while let Some(start) = find(&bytes[pos..], b"<").map(|offset| pos + offset) {
+        let rest = &bytes[start..];
+        let skip_to = |end: &[u8]| find(rest, end).map(|offset| start + offset + end.len());
+        let end = if rest.starts_with(b"<!--") {
+            skip_to(b"-->")
+        } else if rest.starts_with(b"<![CDATA[") {
+            skip_to(b"]]>")
+        } else if rest.starts_with(b"<?") {
+            skip_to(b"?>")
+        } else if rest.starts_with(b"<!DOCTYPE") {
+            let Some(subset) = doctype_subset(rest) else {
+                return max + entity_depth;
+            };
+            let subset_depth = nesting_depth(&xml[start + subset.start..start + subset.end]);
+            entity_depth = subset_depth * MAX_ENTITY_DEPTH;
+            Some(start + subset.end)
+        } else if rest.starts_with(b"<!") {
+            Some(start + 2)
+        } else if rest.starts_with(b"</") {
+            depth = depth.saturating_sub(1);
+            Some(start + 2)
+        } else {
+            let Some(len) = tag_len(rest) else {
+                return max.max(depth + 1) + entity_depth;
+            };
+            if !rest[..len].ends_with(b"/>") {
+                depth += 1;
+                max = max.max(depth);
+            }
+            Some(start + len)
+        };
+        match end {
+            Some(end) => pos = end,
+            None => return max + entity_depth,
+        }
+    }
+    max + entity_depth
+}
+
+/// the range of the internal subset of a `<!DOCTYPE …>`, relative to its start
+fn doctype_subset(doctype: &[u8]) -> Option<core::ops::Range<usize>> {
+    let open = doctype.iter().position(|b| *b == b'[' || *b == b'>')?;
+    if doctype[open] == b'>' {
+        return Some(open..open);
+    }
+    let close = find(&doctype[open..], b"]")? + open;
+    Some(open + 1..close)
+}
+
+/// the length of a start tag, ignoring `>` inside of quoted attribute values
+fn tag_len(tag: &[u8]) -> Option<usize> {
+    let mut quote = None;
+    for (pos, b) in tag.iter().enumerate() {
+        match (quote, b) {
+            (None, b'"' | b'\'') => quote = Some(*b),
+            (Some(q), _) if q == *b => quote = None,
+            (None, b'>') => return Some(pos + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(feature = "std")]
+impl crate::XmlToJson {
+    /// reject documents whose elements are nested deeper than `max_depth` before parsing them,
+    /// default is 256. Parsing and converting recurse per level, so a higher limit may need
+    /// a larger thread stack. `with_max_convert_depth` only limits the converted depth.
+    pub fn with_max_nesting_depth(mut self, max_depth: usize) -> Self {
+        self.max_nesting_depth = max_depth;
+        self
+    }
+
+    /// fail if `xml` is nested deeper than the configured limit
+    pub(crate) fn check_nesting_depth(&self, xml: &str) -> Result<(), String> {
+        let depth = nesting_depth(xml);
+        if depth > self.max_nesting_depth {
+            return Err(format!(
+                "nesting depth {depth} exceeds the limit of {}",
+                self.max_nesting_depth
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_nesting_depth() {
+    assert_eq!(nesting_depth("<a><b x='>'><c/></b><b>t</b></a>"), 2);
+    assert_eq!(
+        nesting_depth("<a><!-- <b><b> --><![CDATA[<b><b>]]><?p <b>?></a>"),
+        1
+    );
+    assert_eq!(nesting_depth("<a><b><c>"), 3);
+    assert_eq!(
+        nesting_depth("<!DOCTYPE a [<!ENTITY e '<b><c/></b>'>]><a>&e;</a>"),
+        11
+    );
+}
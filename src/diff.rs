@@ -0,0 +1,139 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::Value;
+
+/// A single difference between two documents. `path` uses JSON pointer syntax,
+/// `old` or `new` is `None` if the value is missing in that document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffEntry {
+    pub path: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+impl XmlToJson {
+    /// Convert both documents with the same rules and return their structural differences.
+    /// A single element and an array with one element are considered equal.
+    pub fn diff(
+        &self,
+        xml_a: &str,
+        xml_b: &str,
+    ) -> Result<Vec<DiffEntry>, Box<dyn std::error::Error>> {
+        let a = self.xml_to_json(xml_a)?;
+        let b = self.xml_to_json(xml_b)?;
+        let mut entries = Vec::new();
+        Self::diff_values(&a, &b, &mut String::new(), &mut entries);
+        Ok(entries)
+    }
+
+    pub(crate) fn diff_values(
+        a: &Value,
+        b: &Value,
+        path: &mut String,
+        entries: &mut Vec<DiffEntry>,
+    ) {
+        match (a, b) {
+            (Value::Object(map_a), Value::Object(map_b)) => {
+                for (key, val_a) in map_a {
+                    let path_len = Self::push_pointer(path, key);
+                    match map_b.get(key) {
+                        Some(val_b) => Self::diff_values(val_a, val_b, path, entries),
+                        None => entries.push(DiffEntry {
+                            path: path.clone(),
+                            old: Some(val_a.clone()),
+                            new: None,
+                        }),
+                    }
+                    path.truncate(path_len);
+                }
+                for (key, val_b) in map_b.iter().filter(|(key, _)| !map_a.contains_key(*key)) {
+                    let path_len = Self::push_pointer(path, key);
+                    entries.push(DiffEntry {
+                        path: path.clone(),
+                        old: None,
+                        new: Some(val_b.clone()),
+                    });
+                    path.truncate(path_len);
+                }
+            }
+            (Value::Array(_), _) | (_, Value::Array(_)) => {
+                let array_a = Self::as_slice(a);
+                let array_b = Self::as_slice(b);
+                for index in 0..array_a.len().max(array_b.len()) {
+                    let path_len = Self::push_pointer(path, &index.to_string());
+                    match (array_a.get(index), array_b.get(index)) {
+                        (Some(val_a), Some(val_b)) => {
+                            Self::diff_values(val_a, val_b, path, entries)
+                        }
+                        (old, new) => entries.push(DiffEntry {
+                            path: path.clone(),
+                            old: old.cloned(),
+                            new: new.cloned(),
+                        }),
+                    }
+                    path.truncate(path_len);
+                }
+            }
+            _ if a != b => entries.push(DiffEntry {
+                path: path.clone(),
+                old: Some(a.clone()),
+                new: Some(b.clone()),
+            }),
+            _ => {}
+        }
+    }
+
+    fn as_slice(value: &Value) -> &[Value] {
+        match value {
+            Value::Array(array) => array,
+            other => std::slice::from_ref(other),
+        }
+    }
+
+    /// append an escaped JSON pointer segment and return the previous length of `path`
+    fn push_pointer(path: &mut String, key: &str) -> usize {
+        let len = path.len();
+        path.push('/');
+        path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+        len
+    }
+}
+
+#[test]
+fn test_diff() {
+    use serde_json::json;
+
+    let parser = XmlToJson::default();
+    let a = "<a><b href=\"#self\">simple</b><c>C</c></a>";
+    let b = "<a><b href=\"#other\">simple</b><b>2</b><d>D</d></a>";
+    assert_eq!(
+        parser.diff(a, b).unwrap(),
+        vec![
+            DiffEntry {
+                path: "/b/0/@href".into(),
+                old: Some(json!("#self")),
+                new: Some(json!("#other")),
+            },
+            DiffEntry {
+                path: "/b/1".into(),
+                old: None,
+                new: Some(json!({ "#text": "2" })),
+            },
+            DiffEntry {
+                path: "/c".into(),
+                old: Some(json!({ "#text": "C" })),
+                new: None,
+            },
+            DiffEntry {
+                path: "/d".into(),
+                old: None,
+                new: Some(json!({ "#text": "D" })),
+            },
+        ]
+    );
+    assert!(parser.diff(a, a).unwrap().is_empty());
+}
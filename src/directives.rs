@@ -0,0 +1,86 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{JsonType, XmlToJson};
+use serde_json::{Map, Value};
+
+impl XmlToJson {
+    /// Recognize conversion hints in the document. `<?json-array item?>` inside of an element
+    /// converts its children named `item` to an array, even if there is only one.
+    /// `<!-- json:type=number -->` in front of an element sets the type of its text to
+    /// `number`, `bool` or `string`, like `with_type_overrides`.
+    pub fn with_directives(mut self) -> Self {
+        self.directives = true;
+        self
+    }
+
+    /// returns the type of a `json:type` comment in front of `node`
+    pub(crate) fn directive_type(
+        &self,
+        node: &roxmltree::Node,
+    ) -> Result<Option<JsonType>, String> {
+        if !self.directives {
+            return Ok(None);
+        }
+        let comment = node
+            .prev_siblings()
+            .skip(1)
+            .find(|sibling| {
+                !sibling.is_text() || !sibling.text().unwrap_or_default().trim().is_empty()
+            })
+            .filter(|sibling| sibling.is_comment())
+            .and_then(|comment| comment.text()?.trim().strip_prefix("json:type="));
+        match comment.map(str::trim) {
+            None => Ok(None),
+            Some("number") => Ok(Some(JsonType::Number)),
+            Some("bool") => Ok(Some(JsonType::Bool)),
+            Some("string") => Ok(Some(JsonType::String)),
+            Some(other) => Err(format!("unknown json:type '{other}'")),
+        }
+    }
+
+    /// convert children named in `json-array` processing instructions of `node` to arrays
+    pub(crate) fn apply_array_directives(
+        &self,
+        node: &roxmltree::Node,
+        elements: &mut Map<String, Value>,
+    ) {
+        if !self.directives {
+            return;
+        }
+        let names = node
+            .children()
+            .filter_map(|child| child.pi())
+            .filter(|pi| pi.target == "json-array")
+            .flat_map(|pi| pi.value.unwrap_or_default().split_whitespace());
+        for name in names {
+            if let Some(val) = elements.get_mut(name).filter(|val| !val.is_array()) {
+                *val = Value::Array(vec![val.take()]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_directives() {
+    use serde_json::json;
+
+    let xml = "<a><?json-array item?><item>1</item><!-- json:type=number -->\n<n>42</n><!-- json:type=bool --><ok>1</ok></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_directives()
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "item": [{ "#text": "1" }], "n": { "#text": 42 }, "ok": { "#text": true } })
+    );
+    assert_eq!(
+        XmlToJson::default().xml_to_json(xml).unwrap()["item"],
+        json!({ "#text": "1" })
+    );
+    assert!(XmlToJson::default()
+        .with_directives()
+        .xml_to_json("<a><!-- json:type=date --><d>1</d></a>")
+        .is_err());
+}
@@ -0,0 +1,165 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Defines how illegally repeated attributes are handled, see `XmlToJson::with_duplicate_attributes`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DuplicateAttributes {
+    /// keep the first value
+    KeepFirst,
+    /// keep the last value
+    KeepLast,
+    /// collect all values into an array
+    MakeArray,
+}
+
+/// All values of repeated attributes by byte offset of their element in the rewritten document
+pub(crate) type DuplicateTable = HashMap<usize, Vec<(String, Vec<String>)>>;
+
+struct Attribute<'a> {
+    name: &'a str,
+    value: &'a str,
+    raw: &'a str,
+}
+
+/// parse the attributes of a start tag like `<a x="1" y='2'/>`,
+/// returns `None` for malformed tags, which are left to the parser
+fn parse_attributes(tag: &str) -> Option<Vec<Attribute<'_>>> {
+    let mut attributes = Vec::new();
+    let name_end = tag.find(|c: char| c.is_whitespace() || c == '>' || c == '/')?;
+    let mut rest = &tag[name_end..];
+    loop {
+        let start = rest.trim_start();
+        if start.starts_with('>') || start.starts_with("/>") || start.is_empty() {
+            return Some(attributes);
+        }
+        let eq = start.find('=')?;
+        let name = start[..eq].trim();
+        let after_eq = start[eq + 1..].trim_start();
+        let quote = after_eq
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')?;
+        let value_end = after_eq[1..].find(quote)? + 1;
+        let raw_len = start.len() - after_eq.len() + value_end + 1;
+        attributes.push(Attribute {
+            name,
+            value: &after_eq[1..value_end],
+            raw: &start[..raw_len],
+        });
+        rest = &start[raw_len..];
+    }
+}
+
+/// returns the length of a start tag, ignoring `>` inside of quoted attribute values
+fn tag_len(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (pos, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return Some(pos + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+impl DuplicateAttributes {
+    /// remove repeated attributes from all start tags. For `MakeArray`, the first value is kept
+    /// and all values are collected in `table`.
+    pub(crate) fn rewrite<'a>(&self, xml: &'a str, table: &mut DuplicateTable) -> Cow<'a, str> {
+        let mut result = String::new();
+        let mut copied = 0;
+        let mut pos = 0;
+        while let Some(start) = xml[pos..].find('<').map(|offset| pos + offset) {
+            let rest = &xml[start..];
+            let skip_until = [
+                ("<!--", "-->"),
+                ("<![CDATA[", "]]>"),
+                ("<?", "?>"),
+                ("<!", ">"),
+                ("</", ">"),
+            ]
+            .into_iter()
+            .find(|(begin, _)| rest.starts_with(begin));
+            if let Some((_, end)) = skip_until {
+                pos = rest
+                    .find(end)
+                    .map_or(xml.len(), |offset| start + offset + end.len());
+                continue;
+            }
+            let Some(len) = tag_len(rest) else { break };
+            pos = start + len;
+            let tag = &rest[..len];
+            let Some(attributes) = parse_attributes(tag) else {
+                continue;
+            };
+            let mut values: Vec<(&str, Vec<&Attribute>)> = Vec::new();
+            for attr in &attributes {
+                match values.iter_mut().find(|(name, _)| *name == attr.name) {
+                    Some((_, same)) => same.push(attr),
+                    None => values.push((attr.name, vec![attr])),
+                }
+            }
+            if values.len() == attributes.len() {
+                continue;
+            }
+            result.push_str(&xml[copied..start]);
+            let tag_start = result.len();
+            let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(0);
+            result.push_str(&tag[..name_end]);
+            let mut duplicates = Vec::new();
+            for (name, same) in &values {
+                let kept = match self {
+                    DuplicateAttributes::KeepLast => same[same.len() - 1],
+                    _ => same[0],
+                };
+                result.push(' ');
+                result.push_str(kept.raw);
+                if same.len() > 1 {
+                    let all = same.iter().map(|attr| attr.value.to_string()).collect();
+                    duplicates.push((name.to_string(), all));
+                }
+            }
+            result.push_str(if tag.ends_with("/>") { "/>" } else { ">" });
+            if !duplicates.is_empty() {
+                table.insert(tag_start, duplicates);
+            }
+            copied = pos;
+        }
+        if copied == 0 {
+            return Cow::Borrowed(xml);
+        }
+        result.push_str(&xml[copied..]);
+        Cow::Owned(result)
+    }
+}
+
+#[test]
+fn test_rewrite_duplicates() {
+    let xml = "<!-- <b x='0' x='0'> --><a x=\"1\" y='2' x=\"3\"><b x='4'/><c z=\"5\" z=\"6\"/></a>";
+    let mut table = DuplicateTable::new();
+    assert_eq!(
+        DuplicateAttributes::KeepFirst.rewrite(xml, &mut table),
+        "<!-- <b x='0' x='0'> --><a x=\"1\" y='2'><b x='4'/><c z=\"5\"/></a>"
+    );
+    assert_eq!(
+        DuplicateAttributes::KeepLast.rewrite(xml, &mut table),
+        "<!-- <b x='0' x='0'> --><a x=\"3\" y='2'><b x='4'/><c z=\"6\"/></a>"
+    );
+    table.clear();
+    DuplicateAttributes::MakeArray.rewrite(xml, &mut table);
+    assert_eq!(
+        table[&24],
+        vec![("x".to_string(), vec!["1".to_string(), "3".to_string()])]
+    );
+    assert!(matches!(
+        DuplicateAttributes::KeepFirst.rewrite("<a x='1'/>", &mut table),
+        Cow::Borrowed(_)
+    ));
+}
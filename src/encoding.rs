@@ -0,0 +1,150 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::declaration;
+use std::borrow::Cow;
+
+/// Defines how invalid UTF-8 sequences in byte input are handled,
+/// see `XmlToJson::with_utf8_policy`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Utf8Policy {
+    /// fail the conversion
+    #[default]
+    Error,
+    /// insert the replacement character U+FFFD
+    Replace,
+    /// drop the invalid bytes
+    Skip,
+}
+
+impl Utf8Policy {
+    /// decode `bytes` as UTF-8, the byte offsets of invalid sequences are added to `invalid`,
+    /// counted from `offset`
+    fn decode<'a>(
+        &self,
+        bytes: &'a [u8],
+        offset: usize,
+        invalid: &mut Vec<usize>,
+    ) -> Result<Cow<'a, str>, Box<dyn std::error::Error>> {
+        let err = match std::str::from_utf8(bytes) {
+            Ok(text) => return Ok(Cow::Borrowed(text)),
+            Err(err) if *self == Utf8Policy::Error => {
+                return Err(format!("invalid UTF-8 at byte {}", offset + err.valid_up_to()).into())
+            }
+            Err(err) => err,
+        };
+        let mut text = String::with_capacity(bytes.len());
+        let mut pos = 0;
+        let mut err = Some(err);
+        while let Some(found) = err {
+            let valid_end = pos + found.valid_up_to();
+            // the prefix was just validated
+            text.push_str(std::str::from_utf8(&bytes[pos..valid_end]).unwrap_or_default());
+            invalid.push(offset + valid_end);
+            if *self == Utf8Policy::Replace {
+                text.push(char::REPLACEMENT_CHARACTER);
+            }
+            pos = found.error_len().map_or(bytes.len(), |len| valid_end + len);
+            err = std::str::from_utf8(&bytes[pos..]).err();
+        }
+        text.push_str(std::str::from_utf8(&bytes[pos..]).unwrap_or_default());
+        Ok(Cow::Owned(text))
+    }
+}
+
+/// Detect the encoding of `bytes` by its BOM or XML declaration and return the decoded text.
+/// UTF-8 input is only validated and borrowed, UTF-16 and ISO-8859-1 input is decoded.
+/// Invalid UTF-8 is handled by `policy`, with the byte offsets added to `invalid`.
+pub(crate) fn decode_xml<'a>(
+    bytes: &'a [u8],
+    policy: Utf8Policy,
+    invalid: &mut Vec<usize>,
+) -> Result<Cow<'a, str>, Box<dyn std::error::Error>> {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => policy.decode(rest, 3, invalid),
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, false),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, true),
+        [b'<', 0, b'?', 0, ..] => decode_utf16(bytes, false),
+        [0, b'<', 0, b'?', ..] => decode_utf16(bytes, true),
+        _ => match declared_encoding(bytes).map(|enc| enc.to_ascii_lowercase()) {
+            None => policy.decode(bytes, 0, invalid),
+            Some(enc) if enc == "utf-8" || enc == "utf8" || enc == "us-ascii" => {
+                policy.decode(bytes, 0, invalid)
+            }
+            Some(enc) if enc == "iso-8859-1" || enc == "latin1" || enc == "latin-1" => {
+                Ok(Cow::Owned(bytes.iter().map(|b| *b as char).collect()))
+            }
+            Some(enc) => Err(format!("unsupported encoding '{enc}'").into()),
+        },
+    }
+}
+
+fn decode_utf16<'a>(
+    data: &[u8],
+    big_endian: bool,
+) -> Result<Cow<'a, str>, Box<dyn std::error::Error>> {
+    if !data.len().is_multiple_of(2) {
+        return Err("invalid UTF-16 input with odd number of bytes".into());
+    }
+    let units = data.chunks_exact(2).map(|pair| {
+        if big_endian {
+            u16::from_be_bytes([pair[0], pair[1]])
+        } else {
+            u16::from_le_bytes([pair[0], pair[1]])
+        }
+    });
+    Ok(Cow::Owned(
+        char::decode_utf16(units).collect::<Result<String, _>>()?,
+    ))
+}
+
+/// returns the `encoding` of an ASCII compatible XML declaration
+fn declared_encoding(bytes: &[u8]) -> Option<&str> {
+    if !bytes.starts_with(b"<?xml") {
+        return None;
+    }
+    let end = bytes.windows(2).position(|w| w == b"?>")?;
+    let prolog = std::str::from_utf8(&bytes[..end + 2]).ok()?;
+    declaration::pseudo_attribute(declaration::xml_declaration(prolog)?, "encoding")
+}
+
+#[test]
+fn test_decode_xml() {
+    let decode = |bytes| decode_xml(bytes, Utf8Policy::Error, &mut Vec::new());
+    assert!(matches!(decode(b"<a/>").unwrap(), Cow::Borrowed("<a/>")));
+    assert!(matches!(
+        decode(b"\xEF\xBB\xBF<a/>").unwrap(),
+        Cow::Borrowed("<a/>")
+    ));
+    assert_eq!(
+        decode(b"<?xml version='1.0' encoding='ISO-8859-1'?><a>\xE4</a>").unwrap(),
+        "<?xml version='1.0' encoding='ISO-8859-1'?><a>\u{e4}</a>"
+    );
+    assert_eq!(decode(b"\xFF\xFE<\0a\0/\0>\0").unwrap(), "<a/>");
+    assert_eq!(decode(b"\0<\0?\0x\0m\0l\0?\0>").unwrap(), "<?xml?>");
+    assert!(decode(b"<?xml version='1.0' encoding='EUC-JP'?><a/>").is_err());
+    assert!(decode(b"<a>\xFF</a>").is_err());
+}
+
+#[test]
+fn test_utf8_policy() {
+    let mut invalid = Vec::new();
+    assert_eq!(
+        decode_xml(
+            b"\xEF\xBB\xBF<a>\xFFb\xC3</a>",
+            Utf8Policy::Replace,
+            &mut invalid
+        )
+        .unwrap(),
+        "<a>\u{fffd}b\u{fffd}</a>"
+    );
+    assert_eq!(invalid, [6, 8]);
+    assert_eq!(
+        decode_xml(b"<a>\xFF\xFEb</a>", Utf8Policy::Skip, &mut Vec::new()).unwrap(),
+        "<a>b</a>"
+    );
+    let err = decode_xml(b"<a>\xFF</a>", Utf8Policy::Error, &mut Vec::new()).unwrap_err();
+    assert_eq!(err.to_string(), "invalid UTF-8 at byte 3");
+}
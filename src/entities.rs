@@ -0,0 +1,218 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{Context, WarningKind, XmlToJson};
+use std::borrow::Cow;
+
+/// HTML entity names of the characters U+00A0 to U+00FF
+const LATIN1_ENTITIES: [&str; 96] = [
+    "nbsp", "iexcl", "cent", "pound", "curren", "yen", "brvbar", "sect", "uml", "copy", "ordf",
+    "laquo", "not", "shy", "reg", "macr", "deg", "plusmn", "sup2", "sup3", "acute", "micro",
+    "para", "middot", "cedil", "sup1", "ordm", "raquo", "frac14", "frac12", "frac34", "iquest",
+    "Agrave", "Aacute", "Acirc", "Atilde", "Auml", "Aring", "AElig", "Ccedil", "Egrave", "Eacute",
+    "Ecirc", "Euml", "Igrave", "Iacute", "Icirc", "Iuml", "ETH", "Ntilde", "Ograve", "Oacute",
+    "Ocirc", "Otilde", "Ouml", "times", "Oslash", "Ugrave", "Uacute", "Ucirc", "Uuml", "Yacute",
+    "THORN", "szlig", "agrave", "aacute", "acirc", "atilde", "auml", "aring", "aelig", "ccedil",
+    "egrave", "eacute", "ecirc", "euml", "igrave", "iacute", "icirc", "iuml", "eth", "ntilde",
+    "ograve", "oacute", "ocirc", "otilde", "ouml", "divide", "oslash", "ugrave", "uacute", "ucirc",
+    "uuml", "yacute", "thorn", "yuml",
+];
+
+/// other commonly used HTML entities
+const COMMON_ENTITIES: [(&str, u32); 44] = [
+    ("OElig", 338),
+    ("oelig", 339),
+    ("Scaron", 352),
+    ("scaron", 353),
+    ("Yuml", 376),
+    ("fnof", 402),
+    ("circ", 710),
+    ("tilde", 732),
+    ("ensp", 8194),
+    ("emsp", 8195),
+    ("thinsp", 8201),
+    ("zwnj", 8204),
+    ("zwj", 8205),
+    ("ndash", 8211),
+    ("mdash", 8212),
+    ("lsquo", 8216),
+    ("rsquo", 8217),
+    ("sbquo", 8218),
+    ("ldquo", 8220),
+    ("rdquo", 8221),
+    ("bdquo", 8222),
+    ("dagger", 8224),
+    ("Dagger", 8225),
+    ("bull", 8226),
+    ("hellip", 8230),
+    ("permil", 8240),
+    ("prime", 8242),
+    ("Prime", 8243),
+    ("lsaquo", 8249),
+    ("rsaquo", 8250),
+    ("euro", 8364),
+    ("trade", 8482),
+    ("larr", 8592),
+    ("uarr", 8593),
+    ("rarr", 8594),
+    ("darr", 8595),
+    ("harr", 8596),
+    ("minus", 8722),
+    ("infin", 8734),
+    ("asymp", 8776),
+    ("ne", 8800),
+    ("le", 8804),
+    ("ge", 8805),
+    ("loz", 9674),
+];
+
+fn html_entity(name: &str) -> Option<char> {
+    let code = match LATIN1_ENTITIES.iter().position(|entity| *entity == name) {
+        Some(pos) => 0xA0 + pos as u32,
+        None => {
+            COMMON_ENTITIES
+                .iter()
+                .find(|(entity, _)| *entity == name)?
+                .1
+        }
+    };
+    char::from_u32(code)
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+}
+
+/// returns true if `rest` starts with a predefined entity or a character reference
+fn is_xml_reference(rest: &str) -> bool {
+    let Some(end) = rest.find(';') else {
+        return false;
+    };
+    match &rest[1..end] {
+        "amp" | "lt" | "gt" | "quot" | "apos" => true,
+        reference => reference
+            .strip_prefix("#x")
+            .map(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+            .or_else(|| {
+                let digits = reference.strip_prefix('#')?;
+                Some(!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+            })
+            .unwrap_or(false),
+    }
+}
+
+/// escape `text` so that it can be inserted into XML content or attribute values
+fn escape_into(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+impl XmlToJson {
+    fn resolve_entity(&self, name: &str) -> Option<String> {
+        if let Some(text) = self.custom_entities.get(name) {
+            let mut escaped = String::new();
+            escape_into(text, &mut escaped);
+            return Some(escaped);
+        }
+        if self.html_entities {
+            return html_entity(name).map(String::from);
+        }
+        None
+    }
+
+    /// replace named entities that are not predefined by XML, comments and CDATA sections are kept
+    pub(crate) fn resolve_entities<'a>(&self, xml: &'a str, ctx: &mut Context) -> Cow<'a, str> {
+        let active =
+            self.html_entities || !self.custom_entities.is_empty() || self.unknown_entities_as_text;
+        if !active || !xml.contains('&') {
+            return Cow::Borrowed(xml);
+        }
+        let mut result = String::with_capacity(xml.len());
+        let mut rest = xml;
+        while let Some(pos) = rest.find(['&', '<']) {
+            result.push_str(&rest[..pos]);
+            rest = &rest[pos..];
+            let skip_until = if rest.starts_with("<!--") {
+                Some("-->")
+            } else if rest.starts_with("<![CDATA[") {
+                Some("]]>")
+            } else {
+                None
+            };
+            if let Some(end_marker) = skip_until {
+                let end = rest
+                    .find(end_marker)
+                    .map_or(rest.len(), |end| end + end_marker.len());
+                result.push_str(&rest[..end]);
+                rest = &rest[end..];
+            } else if rest.starts_with('<') {
+                result.push('<');
+                rest = &rest[1..];
+            } else {
+                let resolved = rest[1..]
+                    .find(';')
+                    .and_then(|end| Some((end, self.resolve_entity(&rest[1..end + 1])?)));
+                match resolved {
+                    Some((end, text)) => {
+                        result.push_str(&text);
+                        rest = &rest[end + 2..];
+                    }
+                    None if self.unknown_entities_as_text && !is_xml_reference(rest) => {
+                        let name = rest[1..]
+                            .find(';')
+                            .map(|end| &rest[1..end + 1])
+                            .filter(|name| is_name(name))
+                            .unwrap_or("&");
+                        self.record_warning(ctx, WarningKind::UnknownEntity(name.into()));
+                        result.push_str("&amp;");
+                        rest = &rest[1..];
+                    }
+                    None => {
+                        result.push('&');
+                        rest = &rest[1..];
+                    }
+                }
+            }
+        }
+        result.push_str(rest);
+        Cow::Owned(result)
+    }
+}
+
+#[test]
+fn test_resolve_entities() {
+    use std::collections::HashMap;
+
+    let parser = XmlToJson::default().with_html_entities();
+    assert_eq!(
+        parser.resolve_entities(
+            "<a x=\"&auml;\">&nbsp;&amp;&euro;&unknown;<![CDATA[&auml;]]></a>",
+            &mut Context::default()
+        ),
+        "<a x=\"\u{e4}\">\u{a0}&amp;\u{20ac}&unknown;<![CDATA[&auml;]]></a>"
+    );
+    let mut entities = HashMap::new();
+    entities.insert("company".to_string(), "A & B".to_string());
+    let parser = XmlToJson::default().with_entities(entities);
+    assert_eq!(
+        parser.resolve_entities("<a>&company;&auml;</a>", &mut Context::default()),
+        "<a>A &amp; B&auml;</a>"
+    );
+    assert!(matches!(
+        XmlToJson::default().resolve_entities("<a>&auml;</a>", &mut Context::default()),
+        Cow::Borrowed(_)
+    ));
+}
@@ -0,0 +1,131 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{JsonSink, XmlToJson};
+use serde_json::Number;
+
+/// A single JSON event of `XmlToJson::xml_to_events`. The key is `None` for the root value
+/// and for array items.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JsonEvent<'a> {
+    ObjectStart(Option<&'a str>),
+    ObjectEnd,
+    ArrayStart(Option<&'a str>),
+    ArrayEnd,
+    String(Option<&'a str>, &'a str),
+    Number(Option<&'a str>, &'a Number),
+    Bool(Option<&'a str>, bool),
+    Null(Option<&'a str>),
+}
+
+/// passes the events of `xml_to_sink` to a callback, with the key of the next value
+struct EventSink<F> {
+    on_event: F,
+    key: Option<String>,
+}
+
+impl<F: FnMut(JsonEvent)> JsonSink for EventSink<F> {
+    type Output = ();
+    fn null(&mut self) {
+        (self.on_event)(JsonEvent::Null(self.key.take().as_deref()));
+    }
+    fn bool(&mut self, val: bool) {
+        (self.on_event)(JsonEvent::Bool(self.key.take().as_deref(), val));
+    }
+    fn number(&mut self, val: &Number) {
+        (self.on_event)(JsonEvent::Number(self.key.take().as_deref(), val));
+    }
+    fn string(&mut self, val: &str) {
+        (self.on_event)(JsonEvent::String(self.key.take().as_deref(), val));
+    }
+    fn begin_object(&mut self, _len: usize) {
+        (self.on_event)(JsonEvent::ObjectStart(self.key.take().as_deref()));
+    }
+    fn key(&mut self, key: &str) {
+        self.key = Some(key.into());
+    }
+    fn end_object(&mut self) {
+        (self.on_event)(JsonEvent::ObjectEnd);
+    }
+    fn begin_array(&mut self, _len: usize) {
+        (self.on_event)(JsonEvent::ArrayStart(self.key.take().as_deref()));
+    }
+    fn end_array(&mut self) {
+        (self.on_event)(JsonEvent::ArrayEnd);
+    }
+    fn finish(self) {}
+}
+
+impl XmlToJson {
+    /// Parse XML string and emit the converted document as JSON events while traversing it,
+    /// without building a `Value` first. The events describe the same JSON as `xml_to_json`,
+    /// see `xml_to_sink` for the options that need a `Value` anyway.
+    pub fn xml_to_events(
+        &self,
+        xml: &str,
+        on_event: impl FnMut(JsonEvent),
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.xml_to_sink(
+            xml,
+            EventSink {
+                on_event,
+                key: None,
+            },
+        )
+    }
+}
+
+#[test]
+fn test_xml_to_events() {
+    use crate::{JsonType, NullPolicy, ValueSink};
+    use std::collections::HashMap;
+
+    let xml = "<a><b href=\"#self\">simple</b><b><c class=\"my_class\"><d>D</d><d>1</d></c></b><e/><item>i</item><n>2</n><f>true</f></a>";
+    let mut overrides = HashMap::new();
+    overrides.insert("/a/n".to_string(), JsonType::Number);
+    overrides.insert("/a/f".to_string(), JsonType::Bool);
+    let parsers = [
+        XmlToJson::default(),
+        XmlToJson::default()
+            .with_root()
+            .with_null_policy(NullPolicy::Null)
+            .with_plural_names(&[("item", "items")]),
+        XmlToJson::default()
+            .with_key_filter("e", crate::FilterAction::Drop)
+            .with_type_overrides(overrides)
+            .with_array_mode(crate::ArrayMode::AlwaysWrapChildren),
+        XmlToJson::default().with_flatten_text_arrays(),
+    ];
+    for parser in parsers {
+        let mut sink = ValueSink::default();
+        parser
+            .xml_to_events(xml, |event| {
+                let key = match event {
+                    JsonEvent::ObjectStart(key)
+                    | JsonEvent::ArrayStart(key)
+                    | JsonEvent::String(key, _)
+                    | JsonEvent::Number(key, _)
+                    | JsonEvent::Bool(key, _)
+                    | JsonEvent::Null(key) => key,
+                    JsonEvent::ObjectEnd | JsonEvent::ArrayEnd => None,
+                };
+                if let Some(key) = key {
+                    sink.key(key);
+                }
+                match event {
+                    JsonEvent::ObjectStart(_) => sink.begin_object(0),
+                    JsonEvent::ObjectEnd => sink.end_object(),
+                    JsonEvent::ArrayStart(_) => sink.begin_array(0),
+                    JsonEvent::ArrayEnd => sink.end_array(),
+                    JsonEvent::String(_, val) => sink.string(val),
+                    JsonEvent::Number(_, val) => sink.number(val),
+                    JsonEvent::Bool(_, val) => sink.bool(val),
+                    JsonEvent::Null(_) => sink.null(),
+                }
+            })
+            .unwrap();
+        assert_eq!(sink.finish(), parser.xml_to_json(xml).unwrap());
+    }
+}
@@ -0,0 +1,224 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Loads external entities and the external DTD subset, see `XmlToJson::with_entity_resolver`.
+/// Return an error for every reference that isn't allowed.
+pub trait EntityResolver {
+    fn resolve(
+        &self,
+        system_id: &str,
+        public_id: Option<&str>,
+    ) -> Result<String, Box<dyn std::error::Error>>;
+}
+
+/// Resolves only the system ids in the map, all other references are denied
+impl EntityResolver for HashMap<String, String> {
+    fn resolve(
+        &self,
+        system_id: &str,
+        _public_id: Option<&str>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        self.get(system_id)
+            .cloned()
+            .ok_or_else(|| format!("external reference '{system_id}' denied").into())
+    }
+}
+
+impl<F> EntityResolver for F
+where
+    F: Fn(&str, Option<&str>) -> Result<String, Box<dyn std::error::Error>>,
+{
+    fn resolve(
+        &self,
+        system_id: &str,
+        public_id: Option<&str>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        self(system_id, public_id)
+    }
+}
+
+/// parse a quoted literal at the start of `text`, returns its content and the rest
+fn literal(text: &str) -> Option<(&str, &str)> {
+    let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let end = text[1..].find(quote)? + 1;
+    Some((&text[1..end], &text[end + 1..]))
+}
+
+/// parse an optional `SYSTEM "uri"` or `PUBLIC "id" "uri"` at the start of `text`,
+/// returns the system and public id and the rest
+fn external_id(text: &str) -> Option<(&str, Option<&str>, &str)> {
+    if let Some(rest) = text.strip_prefix("SYSTEM") {
+        let (system_id, rest) = literal(rest.trim_start())?;
+        Some((system_id, None, rest))
+    } else {
+        let (public_id, rest) = literal(text.strip_prefix("PUBLIC")?.trim_start())?;
+        let (system_id, rest) = literal(rest.trim_start())?;
+        Some((system_id, Some(public_id), rest))
+    }
+}
+
+/// returns the length of a markup declaration, ignoring `>` inside of quoted literals
+fn declaration_len(text: &str) -> usize {
+    let mut quote = None;
+    for (pos, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return pos + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// remove the text declaration `<?xml …?>` of an external entity
+fn strip_text_declaration(text: &str) -> &str {
+    match text.strip_prefix("<?xml") {
+        Some(rest) => rest.find("?>").map_or(text, |end| &rest[end + 2..]),
+        None => text,
+    }
+}
+
+impl XmlToJson {
+    /// Resolve external entities like `<!ENTITY e SYSTEM "e.xml">` and the external DTD subset
+    /// with `resolver`, requires `with_dtd`. Without a resolver, all external references are
+    /// denied. Use a `HashMap` of system id to content to allow a fixed set of references.
+    pub fn with_entity_resolver(
+        mut self,
+        resolver: impl EntityResolver + Send + Sync + 'static,
+    ) -> Self {
+        self.entity_resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// rewrite the entity declarations of a DTD subset, external entities are replaced by
+    /// internal entities with the resolved content
+    fn resolve_subset(
+        &self,
+        resolver: &(dyn EntityResolver + Send + Sync),
+        subset: &str,
+        out: &mut String,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut rest = subset;
+        while let Some(start) = rest.find('<') {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let len = match rest.starts_with("<!--") {
+                true => rest.find("-->").map_or(rest.len(), |end| end + 3),
+                false => declaration_len(rest),
+            };
+            let declaration = &rest[..len];
+            rest = &rest[len..];
+            let entity = declaration
+                .strip_prefix("<!ENTITY")
+                .map(str::trim_start)
+                .filter(|entity| !entity.starts_with('%'));
+            let Some(entity) = entity else {
+                out.push_str(declaration);
+                continue;
+            };
+            let name_end = entity.find(char::is_whitespace).unwrap_or(entity.len());
+            let external = external_id(entity[name_end..].trim_start())
+                .filter(|(_, _, rest)| !rest.trim_start().starts_with("NDATA"));
+            let Some((system_id, public_id, _)) = external else {
+                out.push_str(declaration);
+                continue;
+            };
+            let content = resolver.resolve(system_id, public_id)?;
+            let content = strip_text_declaration(&content);
+            // character references are expanded when the entity is used, so markup in
+            // the content is kept if it uses only one kind of quote
+            let quote = if content.contains('"') { "'" } else { "\"" };
+            let escaped = content.replace(quote, if quote == "'" { "&#39;" } else { "&#34;" });
+            out.extend([
+                "<!ENTITY ",
+                &entity[..name_end],
+                " ",
+                quote,
+                &escaped,
+                quote,
+                ">",
+            ]);
+        }
+        out.push_str(rest);
+        Ok(())
+    }
+
+    /// inline the external DTD subset and all external entities of the DOCTYPE of `xml`
+    pub(crate) fn resolve_external<'a>(
+        &self,
+        xml: &'a str,
+    ) -> Result<Cow<'a, str>, Box<dyn std::error::Error>> {
+        let (Some(resolver), true) = (&self.entity_resolver, self.allow_dtd) else {
+            return Ok(Cow::Borrowed(xml));
+        };
+        let Some(start) = xml.find("<!DOCTYPE") else {
+            return Ok(Cow::Borrowed(xml));
+        };
+        let doctype = xml[start + 9..].trim_start();
+        let name_end = doctype
+            .find(|c: char| c.is_whitespace() || c == '[' || c == '>')
+            .ok_or("unterminated DOCTYPE")?;
+        let mut rest = doctype[name_end..].trim_start();
+        let mut external = None;
+        if let Some((system_id, public_id, after)) = external_id(rest) {
+            external = Some((system_id, public_id));
+            rest = after.trim_start();
+        }
+        let mut subset = "";
+        if let Some(inner) = rest.strip_prefix('[') {
+            let end = inner
+                .match_indices(']')
+                .map(|(end, _)| end)
+                .find(|end| inner[end + 1..].trim_start().starts_with('>'))
+                .ok_or("unterminated DOCTYPE")?;
+            subset = &inner[..end];
+            rest = inner[end + 1..].trim_start();
+        }
+        let rest = rest.strip_prefix('>').ok_or("unterminated DOCTYPE")?;
+        let mut result = String::with_capacity(xml.len());
+        result.extend([&xml[..start], "<!DOCTYPE ", &doctype[..name_end], " ["]);
+        self.resolve_subset(resolver.as_ref(), subset, &mut result)?;
+        // the internal subset comes first, so its declarations take precedence
+        if let Some((system_id, public_id)) = external {
+            let dtd = resolver.resolve(system_id, public_id)?;
+            self.resolve_subset(resolver.as_ref(), strip_text_declaration(&dtd), &mut result)?;
+        }
+        result.extend(["]>", rest]);
+        Ok(Cow::Owned(result))
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_entity_resolver() {
+    use serde_json::json;
+
+    let xml = r#"<!DOCTYPE a SYSTEM "a.dtd" [<!ENTITY b SYSTEM "b.xml">]><a>&c;&b;</a>"#;
+    let mut allowed = HashMap::new();
+    allowed.insert(
+        "a.dtd".to_string(),
+        r#"<!ENTITY c SYSTEM "c.txt">"#.to_string(),
+    );
+    allowed.insert(
+        "b.xml".to_string(),
+        "<?xml version=\"1.0\"?><b x=\"1\">B</b>".to_string(),
+    );
+    allowed.insert("c.txt".to_string(), "&#60;50%".to_string());
+    let parser = XmlToJson::default()
+        .with_dtd()
+        .with_entity_resolver(allowed);
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({ "#text": "<50%", "b": { "@x": "1", "#text": "B" } })
+    );
+    let denied = r#"<!DOCTYPE a [<!ENTITY d SYSTEM "file:///etc/passwd">]><a>&d;</a>"#;
+    assert!(parser.xml_to_json(denied).is_err());
+    assert!(XmlToJson::default().with_dtd().xml_to_json(denied).is_err());
+}
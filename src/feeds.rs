@@ -0,0 +1,226 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+//! RSS 2.0 and Atom feeds converted into a common JSON structure
+
+use crate::XmlToJson;
+use serde_json::{Map, Value};
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// returns the values of `key` as list, repeated elements may or may not be an array
+fn all<'a>(value: &'a Value, key: &str) -> Vec<&'a Value> {
+    match value.get(key) {
+        Some(Value::Array(array)) => array.iter().collect(),
+        Some(val) => vec![val],
+        None => Vec::new(),
+    }
+}
+
+/// returns the text of the element `key`
+fn text(value: &Value, key: &str) -> Option<String> {
+    let val = all(value, key).into_iter().next()?;
+    val.as_str()
+        .or_else(|| val.get("#text")?.as_str())
+        .map(String::from)
+}
+
+/// convert an RFC 822 date like `Mon, 02 Jan 2006 15:04:05 GMT` into RFC 3339
+fn rfc822_to_rfc3339(date: &str) -> Option<String> {
+    let date = date.split_once(',').map_or(date, |(_, date)| date);
+    let mut parts = date.split_whitespace();
+    let day: u32 = parts.next()?.parse().ok()?;
+    let month_name = parts.next()?;
+    let month = MONTHS.iter().position(|name| *name == month_name)? + 1;
+    let year: u32 = match parts.next()?.parse().ok()? {
+        year @ 0..=49 => year + 2000,
+        year @ 50..=99 => year + 1900,
+        year => year,
+    };
+    let mut time = parts.next()?.split(':');
+    let (hour, minute) = (time.next()?, time.next()?);
+    let second = time.next().unwrap_or("00");
+    let zone = match parts.next().unwrap_or("GMT") {
+        "GMT" | "UT" | "UTC" | "Z" => "Z".to_string(),
+        "EDT" => "-04:00".into(),
+        "EST" | "CDT" => "-05:00".into(),
+        "CST" | "MDT" => "-06:00".into(),
+        "MST" | "PDT" => "-07:00".into(),
+        "PST" => "-08:00".into(),
+        offset if offset.len() == 5 && offset.starts_with(['+', '-']) => {
+            [&offset[..3], ":", &offset[3..]].concat()
+        }
+        _ => return None,
+    };
+    let numbers = [hour, minute, second];
+    if numbers
+        .iter()
+        .any(|num| num.len() != 2 || num.parse::<u8>().is_err())
+    {
+        return None;
+    }
+    Some(format!(
+        "{year:04}-{month:02}-{day:02}T{hour}:{minute}:{second}{zone}"
+    ))
+}
+
+fn insert(map: &mut Map<String, Value>, key: &str, val: Option<String>) {
+    if let Some(val) = val {
+        map.insert(key.into(), Value::String(val));
+    }
+}
+
+fn rss_date(value: &Value, key: &str) -> Option<String> {
+    text(value, key).map(|date| rfc822_to_rfc3339(&date).unwrap_or(date))
+}
+
+/// the `href` of the first alternate link of an Atom element
+fn atom_link(value: &Value) -> Option<String> {
+    all(value, "link")
+        .into_iter()
+        .find(|link| {
+            matches!(
+                link.get("@rel").and_then(Value::as_str),
+                None | Some("alternate")
+            )
+        })
+        .and_then(|link| link.get("@href")?.as_str())
+        .map(String::from)
+}
+
+fn rss_item(item: &Value) -> Value {
+    let mut map = Map::new();
+    insert(
+        &mut map,
+        "id",
+        text(item, "guid").or_else(|| text(item, "link")),
+    );
+    insert(&mut map, "title", text(item, "title"));
+    insert(&mut map, "link", text(item, "link"));
+    insert(&mut map, "summary", text(item, "description"));
+    insert(&mut map, "content", text(item, "encoded"));
+    insert(
+        &mut map,
+        "published",
+        rss_date(item, "pubDate").or_else(|| text(item, "date")),
+    );
+    let authors = ["author", "creator"]
+        .iter()
+        .flat_map(|key| all(item, key))
+        .filter_map(|author| author.get("#text").cloned());
+    map.insert("authors".into(), authors.collect());
+    let categories = all(item, "category")
+        .into_iter()
+        .filter_map(|category| category.get("#text").cloned());
+    map.insert("categories".into(), categories.collect());
+    Value::Object(map)
+}
+
+fn atom_entry(entry: &Value) -> Value {
+    let mut map = Map::new();
+    insert(&mut map, "id", text(entry, "id"));
+    insert(&mut map, "title", text(entry, "title"));
+    insert(&mut map, "link", atom_link(entry));
+    insert(&mut map, "summary", text(entry, "summary"));
+    insert(&mut map, "content", text(entry, "content"));
+    insert(
+        &mut map,
+        "published",
+        text(entry, "published").or_else(|| text(entry, "updated")),
+    );
+    insert(&mut map, "updated", text(entry, "updated"));
+    let authors = all(entry, "author")
+        .into_iter()
+        .filter_map(|author| text(author, "name").map(Value::String));
+    map.insert("authors".into(), authors.collect());
+    let categories = all(entry, "category")
+        .into_iter()
+        .filter_map(|category| category.get("@term").cloned());
+    map.insert("categories".into(), categories.collect());
+    Value::Object(map)
+}
+
+/// Convert an RSS 2.0, RSS 1.0 or Atom feed into a normalized structure:
+/// `{"kind": "rss"|"atom", "title", "link", "description", "updated", "items": [...]}`.
+/// Items contain `id`, `title`, `link`, `summary`, `content`, `published`, `updated`,
+/// `authors` and `categories`, dates are converted to RFC 3339. Missing values are omitted,
+/// `items`, `authors` and `categories` are always arrays.
+pub fn feed_to_json(xml: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    let parser = XmlToJson::default().with_root();
+    let value = parser.xml_to_json(xml)?;
+    let mut feed = Map::new();
+    let items = if let Some(atom) = value.get("feed") {
+        feed.insert("kind".into(), "atom".into());
+        insert(&mut feed, "title", text(atom, "title"));
+        insert(&mut feed, "link", atom_link(atom));
+        insert(&mut feed, "description", text(atom, "subtitle"));
+        insert(&mut feed, "updated", text(atom, "updated"));
+        all(atom, "entry").into_iter().map(atom_entry).collect()
+    } else if let Some(rss) = value.get("rss").or_else(|| value.get("RDF")) {
+        feed.insert("kind".into(), "rss".into());
+        let channel = rss.get("channel").ok_or("missing RSS channel")?;
+        insert(&mut feed, "title", text(channel, "title"));
+        insert(&mut feed, "link", text(channel, "link"));
+        insert(&mut feed, "description", text(channel, "description"));
+        let updated = rss_date(channel, "lastBuildDate").or_else(|| rss_date(channel, "pubDate"));
+        insert(&mut feed, "updated", updated);
+        // RSS 1.0 has the items next to the channel
+        let items = all(channel, "item").into_iter().chain(all(rss, "item"));
+        items.map(rss_item).collect()
+    } else {
+        return Err("neither an RSS nor an Atom feed".into());
+    };
+    feed.insert("items".into(), Value::Array(items));
+    Ok(Value::Object(feed))
+}
+
+#[test]
+fn test_feed_to_json() {
+    use serde_json::json;
+
+    let rss = r#"<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/"><channel>
+        <title>News</title><link>https://example.com</link><description>All news</description>
+        <item><title>First</title><link>https://example.com/1</link>
+        <pubDate>Mon, 02 Jan 2006 15:04:05 -0700</pubDate><dc:creator>Ann</dc:creator>
+        <category>a</category><category>b</category></item>
+    </channel></rss>"#;
+    assert_eq!(
+        feed_to_json(rss).unwrap(),
+        json!({
+            "kind": "rss", "title": "News", "link": "https://example.com", "description": "All news",
+            "items": [{
+                "id": "https://example.com/1", "title": "First", "link": "https://example.com/1",
+                "published": "2006-01-02T15:04:05-07:00", "authors": ["Ann"], "categories": ["a", "b"]
+            }]
+        })
+    );
+
+    let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title>
+        <link rel="self" href="https://example.com/atom"/><link href="https://example.com"/>
+        <updated>2024-01-01T00:00:00Z</updated>
+        <entry><id>urn:1</id><title>Post</title><link href="https://example.com/p"/>
+        <updated>2024-01-01T00:00:00Z</updated><author><name>Bob</name></author>
+        <category term="x"/></entry>
+    </feed>"#;
+    assert_eq!(
+        feed_to_json(atom).unwrap(),
+        json!({
+            "kind": "atom", "title": "Blog", "link": "https://example.com",
+            "updated": "2024-01-01T00:00:00Z",
+            "items": [{
+                "id": "urn:1", "title": "Post", "link": "https://example.com/p",
+                "published": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+                "authors": ["Bob"], "categories": ["x"]
+            }]
+        })
+    );
+    assert_eq!(
+        rfc822_to_rfc3339("Tue, 3 Jun 2008 11:05 GMT").unwrap(),
+        "2008-06-03T11:05:00Z"
+    );
+    assert!(feed_to_json("<html/>").is_err());
+}
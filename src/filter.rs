@@ -0,0 +1,102 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+
+/// Defines what happens to elements and attributes matching a key filter,
+/// see `XmlToJson::with_key_filter`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterAction {
+    /// keep only matching elements and attributes, the root element is always kept
+    Keep,
+    /// drop matching elements and attributes, including all children
+    Drop,
+}
+
+/// match `name` against a glob pattern where `*` matches any sequence and `?` any single
+/// character, without allocating. `p` and `n` are byte offsets at character boundaries.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+    while let Some(c) = name[n..].chars().next() {
+        match pattern[p..].chars().next() {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(expected) if expected == '?' || expected == c => {
+                p += expected.len_utf8();
+                n += c.len_utf8();
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    let skipped = name[matched..].chars().next().map_or(1, char::len_utf8);
+                    backtrack = Some((star, matched + skipped));
+                    p = star + 1;
+                    n = matched + skipped;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].bytes().all(|b| b == b'*')
+}
+
+impl XmlToJson {
+    /// Drop or keep elements and attributes whose local name matches the glob `pattern` during
+    /// conversion, like `internal-*` or `debug?`. `*` matches any sequence of characters and
+    /// `?` a single character, patterns are no regular expressions. Can be called multiple times. Elements and attributes are
+    /// dropped if they match any `Drop` pattern, or if `Keep` patterns exist and they match
+    /// none of them.
+    pub fn with_key_filter(mut self, pattern: &str, action: FilterAction) -> Self {
+        self.key_filters.push((pattern.to_string(), action));
+        self
+    }
+
+    /// returns true if the element or attribute `name` is removed by the key filters
+    pub(crate) fn is_filtered(&self, name: &str) -> bool {
+        let mut has_keep = false;
+        let mut kept = false;
+        for (pattern, action) in &self.key_filters {
+            let matches = matches_pattern(pattern, name);
+            match action {
+                FilterAction::Drop if matches => return true,
+                FilterAction::Drop => {}
+                FilterAction::Keep => {
+                    has_keep = true;
+                    kept |= matches;
+                }
+            }
+        }
+        has_keep && !kept
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_key_filter() {
+    use serde_json::json;
+
+    assert!(matches_pattern("internal-*", "internal-id"));
+    assert!(matches_pattern("*-?d", "internal-id"));
+    assert!(!matches_pattern("debug?", "debug"));
+    assert!(matches_pattern("gr??e*", "größe"));
+    assert!(!matches_pattern("*ß", "straße1"));
+    let xml = r#"<a internal-id="1" href="x"><debug1>d</debug1><b>B</b></a>"#;
+    let dropped = XmlToJson::default()
+        .with_key_filter("internal-*", FilterAction::Drop)
+        .with_key_filter("debug*", FilterAction::Drop);
+    assert_eq!(
+        dropped.xml_to_json(xml).unwrap(),
+        json!({ "@href": "x", "b": { "#text": "B" } })
+    );
+    let kept = XmlToJson::default()
+        .with_key_filter("b", FilterAction::Keep)
+        .with_key_filter("href", FilterAction::Keep);
+    assert_eq!(
+        kept.xml_to_json(xml).unwrap(),
+        json!({ "@href": "x", "b": { "#text": "B" } })
+    );
+}
@@ -0,0 +1,234 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{InlineConflict, XmlToJson};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// Error type of config loaders, which need errors that can be sent between threads
+pub type ConfigLoadError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Loads XML configuration files for config pipelines. With the feature `config`, it is a
+/// `config::Format` for `config::File`, with the feature `figment`, `file` and `string`
+/// return a figment `Provider`. The default converter inlines elements that only contain
+/// text, so `<db><port>5432</port></db>` becomes `{"db": {"port": "5432"}}`. Config loaders
+/// convert such strings to numbers or booleans when the settings are deserialized.
+#[derive(Clone)]
+pub struct XmlConfigFormat {
+    pub converter: Arc<XmlToJson>,
+}
+
+impl Default for XmlConfigFormat {
+    fn default() -> Self {
+        XmlConfigFormat::new(
+            XmlToJson::default().with_inline_simple_children(InlineConflict::Mixed),
+        )
+    }
+}
+
+impl std::fmt::Debug for XmlConfigFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("XmlConfigFormat").finish_non_exhaustive()
+    }
+}
+
+impl XmlConfigFormat {
+    /// use the conversion rules of `converter`
+    pub fn new(converter: XmlToJson) -> Self {
+        XmlConfigFormat {
+            converter: Arc::new(converter),
+        }
+    }
+
+    /// convert the content of the root element of `text` into a map of settings,
+    /// `uri` is only used for error messages
+    pub fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, ConfigLoadError> {
+        let origin = uri.map(String::as_str).unwrap_or("XML config");
+        match self.converter.xml_to_json(text) {
+            Ok(Value::Object(map)) => Ok(map),
+            Ok(Value::Null) => Ok(Map::new()),
+            Ok(_) => Err(format!("{origin}: root element doesn't contain settings").into()),
+            Err(err) => Err(format!("{origin}: {err}").into()),
+        }
+    }
+}
+
+/// the settings of `value` as `config` value with origin `uri`
+#[cfg(feature = "config")]
+fn config_value(uri: Option<&String>, value: Value) -> config::Value {
+    use config::ValueKind;
+
+    let kind = match value {
+        Value::Null => ValueKind::Nil,
+        Value::Bool(val) => ValueKind::Boolean(val),
+        Value::Number(number) => match (number.as_i64(), number.as_u64()) {
+            (Some(val), _) => ValueKind::I64(val),
+            (None, Some(val)) => ValueKind::U64(val),
+            _ => ValueKind::Float(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(text) => ValueKind::String(text),
+        Value::Array(array) => ValueKind::Array(
+            array
+                .into_iter()
+                .map(|val| config_value(uri, val))
+                .collect(),
+        ),
+        Value::Object(map) => ValueKind::Table(
+            map.into_iter()
+                .map(|(key, val)| (key, config_value(uri, val)))
+                .collect(),
+        ),
+    };
+    config::Value::new(uri, kind)
+}
+
+#[cfg(feature = "config")]
+impl config::Format for XmlConfigFormat {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<config::Map<String, config::Value>, ConfigLoadError> {
+        Ok(XmlConfigFormat::parse(self, uri, text)?
+            .into_iter()
+            .map(|(key, val)| (key, config_value(uri, val)))
+            .collect())
+    }
+}
+
+#[cfg(feature = "config")]
+impl config::FileStoredFormat for XmlConfigFormat {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        &["xml"]
+    }
+}
+
+/// A figment `Provider` of an XML configuration, see `XmlConfigFormat::file`
+#[cfg(feature = "figment")]
+pub struct XmlConfigProvider {
+    format: XmlConfigFormat,
+    source: ConfigSource,
+}
+
+#[cfg(feature = "figment")]
+enum ConfigSource {
+    File(std::path::PathBuf),
+    Text(String),
+}
+
+#[cfg(feature = "figment")]
+impl XmlConfigFormat {
+    /// provide the settings of the file at `path` to figment, a missing file is empty
+    /// like with figment's own formats
+    pub fn file(self, path: impl Into<std::path::PathBuf>) -> XmlConfigProvider {
+        XmlConfigProvider {
+            format: self,
+            source: ConfigSource::File(path.into()),
+        }
+    }
+
+    /// provide the settings of `text` to figment
+    pub fn string(self, text: &str) -> XmlConfigProvider {
+        XmlConfigProvider {
+            format: self,
+            source: ConfigSource::Text(text.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "figment")]
+impl figment::Provider for XmlConfigProvider {
+    fn metadata(&self) -> figment::Metadata {
+        match &self.source {
+            ConfigSource::File(path) => {
+                figment::Metadata::named("XML file").source(figment::Source::File(path.clone()))
+            }
+            ConfigSource::Text(_) => figment::Metadata::named("XML source string"),
+        }
+    }
+
+    fn data(
+        &self,
+    ) -> Result<figment::value::Map<figment::Profile, figment::value::Dict>, figment::Error> {
+        let (uri, text) = match &self.source {
+            ConfigSource::File(path) => match std::fs::read_to_string(path) {
+                Ok(text) => (Some(path.display().to_string()), text),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    return Ok(figment::value::Map::new())
+                }
+                Err(err) => return Err(format!("{}: {err}", path.display()).into()),
+            },
+            ConfigSource::Text(text) => (None, text.clone()),
+        };
+        let settings = self
+            .format
+            .parse(uri.as_ref(), &text)
+            .map_err(|err| err.to_string())?;
+        let dict = figment::value::Value::serialize(settings)?
+            .into_dict()
+            .unwrap_or_default();
+        Ok(figment::Profile::Default.collect(dict))
+    }
+}
+
+#[test]
+fn test_xml_config_format() {
+    use serde_json::json;
+
+    let xml = "<config><db port=\"5432\"><host>localhost</host></db><debug>true</debug></config>";
+    let settings = XmlConfigFormat::default().parse(None, xml).unwrap();
+    assert_eq!(
+        Value::Object(settings),
+        json!({ "db": { "@port": "5432", "host": "localhost" }, "debug": "true" })
+    );
+    let uri = "app.xml".to_string();
+    let err = XmlConfigFormat::default()
+        .parse(Some(&uri), "<config>")
+        .unwrap_err();
+    assert!(err.to_string().starts_with("app.xml: "));
+}
+
+#[cfg(feature = "config")]
+#[test]
+fn test_config_format() {
+    let xml = "<config><db port=\"5432\"><host>localhost</host></db><debug>true</debug></config>";
+    let settings = config::Config::builder()
+        .add_source(config::File::from_str(xml, XmlConfigFormat::default()))
+        .build()
+        .unwrap();
+    assert_eq!(settings.get_string("db.host").unwrap(), "localhost");
+    assert!(settings.get_bool("debug").unwrap());
+    let failed = config::Config::builder()
+        .add_source(config::File::from_str(
+            "<config>",
+            XmlConfigFormat::default(),
+        ))
+        .build();
+    assert!(failed.is_err());
+}
+
+#[cfg(feature = "figment")]
+#[test]
+fn test_figment_provider() {
+    use serde_json::json;
+
+    let xml = "<config><db port=\"5432\"><host>localhost</host></db><debug>true</debug></config>";
+    let settings: Value = figment::Figment::from(XmlConfigFormat::default().string(xml))
+        .extract()
+        .unwrap();
+    assert_eq!(
+        settings,
+        json!({ "db": { "@port": "5432", "host": "localhost" }, "debug": "true" })
+    );
+    let missing = XmlConfigFormat::default().file("missing-config.xml");
+    let settings: Value = figment::Figment::from(missing).extract().unwrap();
+    assert_eq!(settings, json!({}));
+    let failed = figment::Figment::from(XmlConfigFormat::default().string("<config>"));
+    assert!(failed.extract::<Value>().is_err());
+}
@@ -0,0 +1,140 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+#[cfg(feature = "roxmltree")]
+use crate::{Context, XmlToJson};
+#[cfg(feature = "roxmltree")]
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(feature = "roxmltree")]
+const NS_XINCLUDE: &str = "http://www.w3.org/2001/XInclude";
+
+/// Loads the documents referenced by `<xi:include href="…"/>`, see `XmlToJson::with_include_resolver`
+pub trait IncludeResolver {
+    fn resolve(&self, href: &str) -> Result<String, Box<dyn std::error::Error>>;
+}
+
+/// Resolves includes relative to a base directory. Absolute hrefs, `..` components and
+/// symbolic links that lead outside of the base directory are rejected.
+pub struct FileResolver {
+    pub base_dir: PathBuf,
+}
+
+impl IncludeResolver for FileResolver {
+    fn resolve(&self, href: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let relative = Path::new(href)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !relative {
+            return Err(format!("include '{href}' is outside of the base directory").into());
+        }
+        let path = self.base_dir.join(href).canonicalize()?;
+        if !path.starts_with(self.base_dir.canonicalize()?) {
+            return Err(format!("include '{href}' is outside of the base directory").into());
+        }
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+
+/// Resolves includes from an in-memory map of href to XML
+impl IncludeResolver for HashMap<String, String> {
+    fn resolve(&self, href: &str) -> Result<String, Box<dyn std::error::Error>> {
+        self.get(href)
+            .cloned()
+            .ok_or_else(|| format!("unknown include '{href}'").into())
+    }
+}
+
+impl<F> IncludeResolver for F
+where
+    F: Fn(&str) -> Result<String, Box<dyn std::error::Error>>,
+{
+    fn resolve(&self, href: &str) -> Result<String, Box<dyn std::error::Error>> {
+        self(href)
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+impl XmlToJson {
+    /// returns true if `node` is an `xi:include` element that should be resolved
+    pub(crate) fn is_include(&self, node: &roxmltree::Node) -> bool {
+        self.include_resolver.is_some()
+            && node.tag_name().name() == "include"
+            && node.tag_name().namespace() == Some(NS_XINCLUDE)
+    }
+
+    /// resolve an `xi:include` element and return name and value of the included root element
+    pub(crate) fn parse_include(
+        &self,
+        node: &roxmltree::Node,
+        ctx: &mut Context,
+    ) -> Result<(String, Option<Value>), Box<dyn std::error::Error>> {
+        let resolver = self
+            .include_resolver
+            .as_ref()
+            .ok_or("no include resolver configured")?;
+        let href = node
+            .attribute("href")
+            .ok_or_else(|| format!("xi:include without href at '{}'", ctx.path))?;
+        if let Some(parse) = node.attribute("parse").filter(|parse| *parse != "xml") {
+            return Err(format!("unsupported xi:include parse='{parse}' at '{}'", ctx.path).into());
+        }
+        if ctx.includes.iter().any(|included| included == href) {
+            return Err(format!("recursive xi:include of '{href}' at '{}'", ctx.path).into());
+        }
+        let xml = resolver.resolve(href)?;
+        self.check_nesting_depth(&xml)?;
+        let doc = roxmltree::Document::parse_with_options(&xml, self.parsing_options())?;
+        let root = doc.root_element();
+        let name = self.element_name(&root).into_owned();
+        ctx.includes.push(href.to_string());
+        let path_len = ctx.enter(&name);
+        let value = self.parse_node(&root, ctx)?;
+        ctx.leave(path_len);
+        ctx.includes.pop();
+        Ok((name, value))
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_include_resolver() {
+    use serde_json::json;
+
+    let xml =
+        "<a xmlns:xi=\"http://www.w3.org/2001/XInclude\"><xi:include href=\"b.xml\"/><c>C</c></a>";
+    let mut includes = HashMap::new();
+    includes.insert("b.xml".to_string(), "<b><d>D</d></b>".to_string());
+    assert_eq!(
+        XmlToJson::default()
+            .with_include_resolver(includes)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b": { "d": { "#text": "D" } }, "c": { "#text": "C" } })
+    );
+    let recursive = |_: &str| -> Result<String, Box<dyn std::error::Error>> {
+        Ok(
+            "<b xmlns:xi=\"http://www.w3.org/2001/XInclude\"><xi:include href=\"b.xml\"/></b>"
+                .into(),
+        )
+    };
+    assert!(XmlToJson::default()
+        .with_include_resolver(recursive)
+        .xml_to_json(xml)
+        .is_err());
+}
+
+#[test]
+fn test_file_resolver_stays_in_base_dir() {
+    let resolver = FileResolver {
+        base_dir: std::env::temp_dir(),
+    };
+    for href in ["/etc/passwd", "../etc/passwd", "a/../../etc/passwd"] {
+        let err = resolver.resolve(href).unwrap_err();
+        assert!(err.to_string().contains("outside of the base directory"));
+    }
+}
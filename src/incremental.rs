@@ -0,0 +1,290 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::Value;
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// returns the length of the markup at the start of `data`, or None if it is incomplete
+fn markup_len(data: &[u8]) -> Option<usize> {
+    if data.starts_with(b"<!--") {
+        find(data, b"-->").map(|end| end + 3)
+    } else if data.starts_with(b"<![CDATA[") {
+        find(data, b"]]>").map(|end| end + 3)
+    } else if data.starts_with(b"<?") {
+        find(data, b"?>").map(|end| end + 2)
+    } else {
+        // ignore `>` inside of quoted attribute values
+        let mut quote = None;
+        for (pos, &byte) in data.iter().enumerate() {
+            match (quote, byte) {
+                (None, b'"' | b'\'') => quote = Some(byte),
+                (Some(q), _) if q == byte => quote = None,
+                (None, b'>') => return Some(pos + 1),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// A tag that changes the nesting of records
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum RecordTag {
+    Start,
+    End,
+    Empty,
+}
+
+/// returns the kind of `tag` if it is a start, end or empty tag of an element named `name`
+pub(crate) fn record_tag(tag: &[u8], name: &[u8]) -> Option<RecordTag> {
+    if tag.starts_with(b"<!") || tag.starts_with(b"<?") {
+        return None;
+    }
+    let closing = tag.starts_with(b"</");
+    let tag_name = &tag[1 + closing as usize..];
+    let name_len = tag_name
+        .iter()
+        .position(|byte| byte.is_ascii_whitespace() || b"/>".contains(byte))
+        .unwrap_or(tag_name.len());
+    if &tag_name[..name_len] != name {
+        None
+    } else if closing {
+        Some(RecordTag::End)
+    } else if tag.ends_with(b"/>") {
+        Some(RecordTag::Empty)
+    } else {
+        Some(RecordTag::Start)
+    }
+}
+
+/// returns the byte ranges of all outermost elements named `name` in a complete document
+pub(crate) fn record_ranges(data: &[u8], name: &[u8]) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut record: Option<(usize, usize)> = None;
+    let mut pos = 0;
+    while let Some(start) = find(&data[pos..], b"<").map(|offset| pos + offset) {
+        let Some(len) = markup_len(&data[start..]) else {
+            break;
+        };
+        pos = start + len;
+        match (record_tag(&data[start..pos], name), record.as_mut()) {
+            (None, _) | (Some(RecordTag::Empty), Some(_)) => {}
+            (Some(RecordTag::Start), Some((_, nesting))) => *nesting += 1,
+            (Some(RecordTag::End), Some((record_start, nesting))) => {
+                *nesting -= 1;
+                if *nesting == 0 {
+                    ranges.push(*record_start..pos);
+                    record = None;
+                }
+            }
+            (Some(RecordTag::Start), None) => record = Some((start, 1)),
+            (Some(RecordTag::Empty), None) => ranges.push(start..pos),
+            (Some(RecordTag::End), None) => {}
+        }
+    }
+    ranges
+}
+
+/// Push parser for XML arriving in arbitrary chunks, see `XmlToJson::into_incremental`.
+///
+/// By default, the chunks are collected and converted by `finish`. With `with_records`, each
+/// record element is converted as soon as it is complete and its input is released, so only
+/// the currently open record is kept in memory. Record detection requires an ASCII compatible
+/// encoding, like UTF-8.
+pub struct Incremental {
+    parser: XmlToJson,
+    record_name: Option<String>,
+    buffer: Vec<u8>,
+    /// end of the markup that was already scanned for records
+    scanned: usize,
+    /// start offset of the currently open record and the number of open elements named like it
+    record: Option<(usize, usize)>,
+    /// completed records and the input offset behind each of them
+    records: Vec<(Value, u64)>,
+    /// input offset of the start of `buffer`
+    offset: u64,
+    /// input offset behind the last completed record
+    checkpoint: u64,
+}
+
+impl XmlToJson {
+    /// Create a push parser with the default configuration
+    pub fn incremental() -> Incremental {
+        XmlToJson::default().into_incremental()
+    }
+
+    /// Create a push parser that uses this configuration
+    pub fn into_incremental(self) -> Incremental {
+        Incremental {
+            parser: self,
+            record_name: None,
+            buffer: Vec::new(),
+            scanned: 0,
+            record: None,
+            records: Vec::new(),
+            offset: 0,
+            checkpoint: 0,
+        }
+    }
+}
+
+impl Incremental {
+    /// convert every element named `name` as a separate record, for example each `<entry>` of
+    /// a feed. Nested elements with the same name are part of the outer record. A record ends
+    /// with its matching end tag, even if it contains unclosed elements, so that a broken
+    /// record doesn't affect the next one.
+    pub fn with_records(mut self, name: &str) -> Self {
+        self.record_name = Some(String::from(name));
+        self
+    }
+
+    /// continue an interrupted conversion, the next pushed chunk starts at byte `offset` of the
+    /// input. Use a checkpoint, so that the input starts behind a record.
+    pub fn resume_from(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self.checkpoint = offset;
+        self
+    }
+
+    /// the input offset behind the last completed record, also if it couldn't be converted.
+    /// Records in front of it don't need to be pushed again after an interruption.
+    pub fn checkpoint(&self) -> u64 {
+        self.checkpoint
+    }
+
+    /// append the next chunk of input. If a record can't be converted, the error is returned
+    /// and the following records are converted by the next call, which may push an empty chunk.
+    pub fn push(&mut self, bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        self.buffer.extend_from_slice(bytes);
+        if self.record_name.is_some() {
+            self.scan()?;
+        }
+        Ok(())
+    }
+
+    /// take the records that were completed so far
+    pub fn records(&mut self) -> Vec<Value> {
+        let records = std::mem::take(&mut self.records);
+        records.into_iter().map(|(record, _)| record).collect()
+    }
+
+    /// take the records that were completed so far, with the input offset behind each of them
+    pub fn records_with_offsets(&mut self) -> Vec<(Value, u64)> {
+        std::mem::take(&mut self.records)
+    }
+
+    /// finish the input and return the converted document. With `with_records`, the remaining
+    /// records are returned as array and an unfinished record is an error.
+    pub fn finish(self) -> Result<Value, Box<dyn std::error::Error>> {
+        if self.record_name.is_none() {
+            return self.parser.xml_to_json_bytes(&self.buffer);
+        }
+        if self.record.is_some() {
+            return Err("unexpected end of input inside of a record".into());
+        }
+        Ok(self.records.into_iter().map(|(record, _)| record).collect())
+    }
+
+    fn scan(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let name = self.record_name.as_deref().unwrap_or_default().as_bytes();
+        let mut pos = self.scanned;
+        let mut failed = None;
+        while let Some(start) = find(&self.buffer[pos..], b"<").map(|offset| pos + offset) {
+            pos = start;
+            let Some(len) = markup_len(&self.buffer[start..]) else {
+                break;
+            };
+            let tag = &self.buffer[start..start + len];
+            pos = start + len;
+            let mut record_end = None;
+            // only elements named like the record change the nesting
+            match (record_tag(tag, name), self.record.as_mut()) {
+                (None, _) | (Some(RecordTag::Empty), Some(_)) => {}
+                (Some(RecordTag::Start), Some((_, nesting))) => *nesting += 1,
+                (Some(RecordTag::End), Some((record_start, nesting))) => {
+                    *nesting -= 1;
+                    if *nesting == 0 {
+                        record_end = Some(*record_start);
+                    }
+                }
+                (Some(RecordTag::Start), None) => self.record = Some((start, 1)),
+                (Some(RecordTag::Empty), None) => {
+                    self.record = Some((start, 1));
+                    record_end = Some(start);
+                }
+                (Some(RecordTag::End), None) => {}
+            }
+            if let Some(record_start) = record_end {
+                self.record = None;
+                self.checkpoint = self.offset + pos as u64;
+                match std::str::from_utf8(&self.buffer[record_start..pos]) {
+                    Ok(record) => match self.parser.xml_to_json(record) {
+                        Ok(value) => self.records.push((value, self.checkpoint)),
+                        Err(err) => failed = Some(err),
+                    },
+                    Err(err) => failed = Some(err.into()),
+                }
+                if failed.is_some() {
+                    break;
+                }
+            }
+        }
+        if failed.is_none() && !self.buffer[pos..].contains(&b'<') {
+            pos = self.buffer.len();
+        }
+        // release the input in front of the open record
+        let release = self.record.map_or(pos, |(record_start, _)| record_start);
+        self.buffer.drain(..release);
+        self.offset += release as u64;
+        self.scanned = pos - release;
+        if let Some((record_start, _)) = self.record.as_mut() {
+            *record_start -= release;
+        }
+        failed.map_or(Ok(()), Err)
+    }
+}
+
+#[test]
+fn test_incremental() {
+    use serde_json::json;
+
+    let xml = "<?xml version=\"1.0\"?><feed><entry id=\"1\">a<!-- <entry> --></entry>\
+        <other/><entry id=\"2\"><entry>nested</entry></entry><entry id=\"3\"/></feed>";
+    let mut conv = XmlToJson::incremental();
+    for chunk in xml.as_bytes().chunks(3) {
+        conv.push(chunk).unwrap();
+    }
+    assert_eq!(
+        conv.finish().unwrap(),
+        XmlToJson::default().xml_to_json(xml).unwrap()
+    );
+
+    let mut conv = XmlToJson::incremental().with_records("entry");
+    let mut records = Vec::new();
+    for chunk in xml.as_bytes().chunks(5) {
+        conv.push(chunk).unwrap();
+        records.extend(conv.records());
+    }
+    assert!(conv.buffer.len() < 5);
+    assert_eq!(conv.finish().unwrap(), json!([]));
+    assert_eq!(
+        records,
+        [
+            json!({ "@id": "1", "#text": "a" }),
+            json!({ "@id": "2", "entry": { "#text": "nested" } }),
+            json!({ "@id": "3" }),
+        ]
+    );
+
+    let mut conv = XmlToJson::incremental().with_records("entry");
+    conv.push(b"<feed><entry>").unwrap();
+    assert!(conv.finish().is_err());
+}
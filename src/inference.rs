@@ -0,0 +1,138 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{JsonType, NumberMode, XmlToJson};
+use std::collections::{BTreeSet, HashMap};
+
+/// Array and type rules learned from sample documents, see `TypeInference::learn`.
+/// Paths use the syntax of `XmlToJson::with_type_overrides` with the default attribute prefix.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InferredRules {
+    /// paths of elements that are repeated in at least one document
+    pub array_paths: Vec<String>,
+    /// paths of element texts and attributes whose values are all numbers or all booleans
+    pub type_overrides: HashMap<String, JsonType>,
+}
+
+/// Learns `InferredRules` from a sample of documents, so that documents where an element
+/// occurs only once still get an array and values get the same type in every document
+pub struct TypeInference;
+
+/// whether all values seen at a path are numbers and whether all are booleans
+type ValueKinds = HashMap<String, (bool, bool)>;
+
+impl TypeInference {
+    /// analyze `docs`, fails if one of them isn't well-formed or nested deeper than the
+    /// default of `XmlToJson::with_max_nesting_depth`
+    pub fn learn(docs: &[&str]) -> Result<InferredRules, Box<dyn std::error::Error>> {
+        let mut arrays = BTreeSet::new();
+        let mut kinds = ValueKinds::new();
+        let parser = XmlToJson::default();
+        for doc in docs {
+            parser.check_nesting_depth(doc)?;
+            let doc = roxmltree::Document::parse(doc)?;
+            let root = doc.root_element();
+            let mut path = ["/", root.tag_name().name()].concat();
+            Self::learn_node(&root, &mut path, &mut arrays, &mut kinds);
+        }
+        let type_overrides = kinds
+            .into_iter()
+            .filter_map(|(path, kind)| match kind {
+                (true, _) => Some((path, JsonType::Number)),
+                (false, true) => Some((path, JsonType::Bool)),
+                (false, false) => None,
+            })
+            .collect();
+        Ok(InferredRules {
+            array_paths: arrays.into_iter().collect(),
+            type_overrides,
+        })
+    }
+
+    fn learn_node(
+        node: &roxmltree::Node,
+        path: &mut String,
+        arrays: &mut BTreeSet<String>,
+        kinds: &mut ValueKinds,
+    ) {
+        if let Some(text) = node.text().map(str::trim).filter(|text| !text.is_empty()) {
+            Self::observe(kinds, path.clone(), text);
+        }
+        for attr in node.attributes() {
+            Self::observe(
+                kinds,
+                [path, "/@", attr.name()].concat(),
+                attr.value().trim(),
+            );
+        }
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for child in node.children().filter(|child| child.is_element()) {
+            let name = child.tag_name().name();
+            let count = counts.entry(name).or_default();
+            *count += 1;
+            let path_len = path.len();
+            path.push('/');
+            path.push_str(name);
+            if *count > 1 {
+                arrays.insert(path.clone());
+            }
+            Self::learn_node(&child, path, arrays, kinds);
+            path.truncate(path_len);
+        }
+    }
+
+    fn observe(kinds: &mut ValueKinds, path: String, value: &str) {
+        // leading zeros, like in zip codes, would be lost in a number
+        let leading_zero = value.len() > 1 && value.starts_with('0') && !value.starts_with("0.");
+        let number = !leading_zero
+            && JsonType::Number
+                .convert(value, NumberMode::String)
+                .is_ok_and(|number| number.is_number());
+        let boolean = value == "true" || value == "false";
+        let (all_numbers, all_booleans) = kinds.entry(path).or_insert((true, true));
+        *all_numbers &= number;
+        *all_booleans &= boolean;
+    }
+}
+
+impl XmlToJson {
+    /// apply rules of `TypeInference::learn`, in addition to previous type overrides and
+    /// array paths. Set the attribute prefix before, attribute paths are adjusted to it.
+    pub fn with_inferred_rules(mut self, rules: &InferredRules) -> Self {
+        self.array_paths.extend(rules.array_paths.iter().cloned());
+        for (path, json_type) in &rules.type_overrides {
+            let path = match path.rsplit_once("/@") {
+                Some((element, name)) => [element, "/", &self.attribute_prefix, name].concat(),
+                None => path.clone(),
+            };
+            self.type_overrides.insert(path, *json_type);
+        }
+        self
+    }
+}
+
+#[test]
+fn test_type_inference() {
+    use serde_json::json;
+
+    let docs = [
+        r#"<order id="1"><item><qty>2</qty><sku>007</sku></item><item><qty>1</qty></item><paid>true</paid></order>"#,
+        r#"<order id="2"><item><qty>3</qty><sku>12</sku></item><paid>false</paid></order>"#,
+    ];
+    let rules = TypeInference::learn(&docs).unwrap();
+    assert_eq!(rules.array_paths, ["/order/item"]);
+    assert_eq!(rules.type_overrides.len(), 3);
+    let parser = XmlToJson::default()
+        .with_attribute_prefix("_")
+        .with_inferred_rules(&rules);
+    assert_eq!(
+        parser.xml_to_json(docs[1]).unwrap(),
+        json!({
+            "_id": 2,
+            "item": [{ "qty": { "#text": 3 }, "sku": { "#text": "12" } }],
+            "paid": { "#text": false },
+        })
+    );
+}
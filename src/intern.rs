@@ -0,0 +1,182 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{JsonSink, XmlToJson};
+use serde_json::{Map, Number, Value};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A JSON value that shares repeated strings and keys, see `XmlToJson::xml_to_interned`
+#[derive(Clone, Debug, PartialEq)]
+pub enum InternedValue {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(Arc<str>),
+    Array(Vec<InternedValue>),
+    Object(Vec<(Arc<str>, InternedValue)>),
+}
+
+impl InternedValue {
+    /// returns the value of `key` if this is an object
+    pub fn get(&self, key: &str) -> Option<&InternedValue> {
+        match self {
+            InternedValue::Object(entries) => entries
+                .iter()
+                .find(|(name, _)| &**name == key)
+                .map(|(_, val)| val),
+            _ => None,
+        }
+    }
+
+    /// convert into a `serde_json::Value`, which copies every string
+    pub fn to_value(&self) -> Value {
+        match self {
+            InternedValue::Null => Value::Null,
+            InternedValue::Bool(val) => Value::Bool(*val),
+            InternedValue::Number(val) => Value::Number(val.clone()),
+            InternedValue::String(val) => Value::String(val.to_string()),
+            InternedValue::Array(array) => array.iter().map(InternedValue::to_value).collect(),
+            InternedValue::Object(entries) => Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, val)| (key.to_string(), val.to_value()))
+                    .collect::<Map<_, _>>(),
+            ),
+        }
+    }
+}
+
+/// Builds an `InternedValue`, each distinct string is only allocated once
+#[derive(Default)]
+pub struct InterningSink {
+    strings: HashSet<Arc<str>>,
+    stack: Vec<(InternedValue, Option<Arc<str>>)>,
+    key: Option<Arc<str>>,
+    result: Option<InternedValue>,
+}
+
+impl InterningSink {
+    fn intern(&mut self, val: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(val) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(val);
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    fn push_value(&mut self, val: InternedValue) {
+        match self.stack.last_mut() {
+            Some((InternedValue::Object(entries), _)) => {
+                let key = self.key.take().unwrap_or_else(|| Arc::from(""));
+                entries.push((key, val));
+            }
+            Some((InternedValue::Array(array), _)) => array.push(val),
+            _ => self.result = Some(val),
+        }
+    }
+
+    fn end_container(&mut self) {
+        if let Some((val, key)) = self.stack.pop() {
+            self.key = key;
+            self.push_value(val);
+        }
+    }
+}
+
+impl JsonSink for InterningSink {
+    type Output = InternedValue;
+    fn null(&mut self) {
+        self.push_value(InternedValue::Null);
+    }
+    fn bool(&mut self, val: bool) {
+        self.push_value(InternedValue::Bool(val));
+    }
+    fn number(&mut self, val: &Number) {
+        self.push_value(InternedValue::Number(val.clone()));
+    }
+    fn string(&mut self, val: &str) {
+        let val = self.intern(val);
+        self.push_value(InternedValue::String(val));
+    }
+    fn begin_object(&mut self, len: usize) {
+        let key = self.key.take();
+        self.stack
+            .push((InternedValue::Object(Vec::with_capacity(len)), key));
+    }
+    fn key(&mut self, key: &str) {
+        self.key = Some(self.intern(key));
+    }
+    fn end_object(&mut self) {
+        self.end_container();
+    }
+    fn begin_array(&mut self, len: usize) {
+        let key = self.key.take();
+        self.stack
+            .push((InternedValue::Array(Vec::with_capacity(len)), key));
+    }
+    fn end_array(&mut self) {
+        self.end_container();
+    }
+    fn finish(self) -> InternedValue {
+        self.result.unwrap_or(InternedValue::Null)
+    }
+}
+
+impl XmlToJson {
+    /// Parse XML string into an `InternedValue`, where repeated keys and values like
+    /// `status="OK"` share a single allocation. This keeps the memory of large, repetitive
+    /// results small. Strings are interned while traversing the XML, so the repeated copies
+    /// are never allocated unless an option of `xml_to_sink` needs a `Value` first.
+    /// `tests/interning_memory.rs` compares the peak memory with `xml_to_json`.
+    pub fn xml_to_interned(&self, xml: &str) -> Result<InternedValue, Box<dyn std::error::Error>> {
+        self.xml_to_sink(xml, InterningSink::default())
+    }
+}
+
+#[test]
+fn test_xml_to_interned() {
+    /// bytes allocated for strings and keys
+    fn string_bytes(value: &Value) -> usize {
+        match value {
+            Value::String(val) => val.len(),
+            Value::Array(array) => array.iter().map(string_bytes).sum(),
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, val)| key.len() + string_bytes(val))
+                .sum(),
+            _ => 0,
+        }
+    }
+
+    /// bytes allocated for strings and keys, each shared string is counted once
+    fn interned_bytes(value: &InternedValue, seen: &mut HashSet<*const u8>) -> usize {
+        fn count(val: &Arc<str>, seen: &mut HashSet<*const u8>) -> usize {
+            match seen.insert(val.as_ptr()) {
+                true => val.len(),
+                false => 0,
+            }
+        }
+        match value {
+            InternedValue::String(val) => count(val, seen),
+            InternedValue::Array(array) => array.iter().map(|val| interned_bytes(val, seen)).sum(),
+            InternedValue::Object(entries) => entries
+                .iter()
+                .map(|(key, val)| count(key, seen) + interned_bytes(val, seen))
+                .sum(),
+            _ => 0,
+        }
+    }
+
+    let xml = format!("<a>{}</a>", "<row status=\"OK\"/>".repeat(1000));
+    let parser = XmlToJson::default();
+    let interned = parser.xml_to_interned(&xml).unwrap();
+    let value = parser.xml_to_json(&xml).unwrap();
+    assert_eq!(interned.to_value(), value);
+    // 1000 copies of "@status" and "OK" compared to a single one
+    assert_eq!(string_bytes(&value), 3 + 9000);
+    assert_eq!(interned_bytes(&interned, &mut HashSet::new()), 3 + 9);
+}
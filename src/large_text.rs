@@ -0,0 +1,54 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::Value;
+use std::io::Read;
+
+impl XmlToJson {
+    /// pass element texts longer than `threshold` bytes to `handler` instead of converting them.
+    /// The handler gets the path of the element and a reader over the trimmed text, it can
+    /// write the text to a file or blob store and return a placeholder like an URI, which is
+    /// used as text value. Attribute values and binary elements aren't passed to the handler.
+    /// The whole document and with it the text is still held in memory while parsing, the
+    /// reader only reads from that buffer. The handler keeps the text out of the JSON
+    /// output, so it isn't copied and held a second time.
+    pub fn with_large_text_handler(
+        mut self,
+        threshold: usize,
+        handler: impl Fn(&str, &mut dyn Read) -> Value + Send + Sync + 'static,
+    ) -> Self {
+        self.large_text_handler = Some((threshold, Box::new(handler)));
+        self
+    }
+
+    /// the placeholder of the handler if `text` exceeds the threshold
+    pub(crate) fn large_text(&self, path: &str, text: &str) -> Option<Value> {
+        let (threshold, handler) = self.large_text_handler.as_ref()?;
+        (text.len() > *threshold).then(|| handler(path, &mut text.as_bytes()))
+    }
+}
+
+#[test]
+fn test_large_text_handler() {
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    let stored = Arc::new(Mutex::new(Vec::new()));
+    let store = stored.clone();
+    let parser = XmlToJson::default().with_large_text_handler(8, move |path, reader| {
+        let mut blob = String::new();
+        reader.read_to_string(&mut blob).unwrap();
+        let mut stored = store.lock().unwrap();
+        stored.push(blob);
+        json!(format!("blob://{}{path}", stored.len()))
+    });
+    let xml = "<a><small>1234</small><payload> aGVsbG8gd29ybGQ= </payload></a>";
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({ "small": { "#text": "1234" }, "payload": { "#text": "blob://1/a/payload" } })
+    );
+    assert_eq!(*stored.lock().unwrap(), ["aGVsbG8gd29ybGQ="]);
+}
@@ -0,0 +1,45 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde::ser::{Error, Serialize, Serializer};
+use std::fmt;
+
+/// An XML string that is converted with the default options when it gets serialized,
+/// so it can be embedded into larger JSON responses without converting it first.
+/// `Display` writes the converted JSON.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JsonFromXml<'a>(pub &'a str);
+
+impl Serialize for JsonFromXml<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        XmlToJson::default()
+            .xml_to_json(self.0)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl fmt::Display for JsonFromXml<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = XmlToJson::default()
+            .xml_to_json(self.0)
+            .map_err(|_| fmt::Error)?;
+        write!(f, "{value}")
+    }
+}
+
+#[test]
+fn test_json_from_xml() {
+    use serde_json::json;
+
+    let response = json!({ "id": 1, "payload": JsonFromXml("<a><b>B</b></a>") });
+    assert_eq!(
+        response,
+        json!({ "id": 1, "payload": { "b": { "#text": "B" } } })
+    );
+    assert_eq!(JsonFromXml("<a x=\"1\"/>").to_string(), r#"{"@x":"1"}"#);
+    assert!(serde_json::to_string(&JsonFromXml("<a>")).is_err());
+}
@@ -3,31 +3,570 @@
 //  Licensed under MIT License, see License file for more details
 //  git clone https://github.com/marcomq/xml_to_json_rs
 
-use roxmltree;
+// the quick-xml backend only supports the basic options
+#![cfg_attr(not(feature = "roxmltree"), allow(dead_code))]
+// without `std` only the basic converter in `alloc_only` is available
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
 use serde_json::{Map, Value};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(all(not(feature = "std"), feature = "roxmltree"))]
+mod alloc_only;
+#[cfg(feature = "avro")]
+mod avro;
+#[cfg(feature = "std")]
+mod binary;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+mod borrowed;
+#[cfg(feature = "bson")]
+mod bson;
+#[cfg(feature = "std")]
+mod callback;
+#[cfg(feature = "std")]
+mod cancel;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+mod collect;
+#[cfg(feature = "std")]
+mod config;
+#[cfg(feature = "std")]
+mod datetime;
+#[cfg(feature = "std")]
+mod declaration;
+#[cfg(feature = "std")]
+mod dedup;
+mod depth;
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+mod directives;
+#[cfg(feature = "std")]
+mod duplicates;
+#[cfg(feature = "std")]
+mod encoding;
+#[cfg(feature = "std")]
+mod entities;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+mod events;
+#[cfg(feature = "std")]
+mod external;
+#[cfg(feature = "std")]
+mod feeds;
+#[cfg(feature = "std")]
+mod filter;
+#[cfg(feature = "std")]
+mod format;
+#[cfg(feature = "std")]
+mod include;
+#[cfg(feature = "std")]
+mod incremental;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+mod inference;
+#[cfg(feature = "std")]
+mod intern;
+#[cfg(feature = "std")]
+mod large_text;
+#[cfg(feature = "std")]
+mod lazy;
+#[cfg(feature = "std")]
+mod log_reader;
+#[cfg(feature = "std")]
+mod merge;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+#[cfg(feature = "std")]
+mod parallel;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+mod partial;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+mod pointer;
+#[cfg(feature = "std")]
+mod presence;
+#[cfg(feature = "std")]
+mod profile;
+#[cfg(feature = "std")]
+mod promote;
+#[cfg(feature = "std")]
+mod query;
+#[cfg(feature = "quick-xml")]
+mod quick;
+#[cfg(feature = "std")]
+mod records;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod roundtrip;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+mod roxml;
+#[cfg(feature = "std")]
+mod sink;
+#[cfg(feature = "std")]
+mod spec;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod template;
+#[cfg(feature = "std")]
+mod trace;
+#[cfg(feature = "std")]
+mod transform;
+#[cfg(feature = "std")]
+mod tree;
+#[cfg(feature = "std")]
+mod types;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+mod visitor;
+#[cfg(feature = "web")]
+mod web;
+#[cfg(feature = "std")]
+mod writer;
+
+#[cfg(all(not(feature = "std"), feature = "roxmltree"))]
+pub use alloc_only::{ParseError, XmlToJson};
+#[cfg(feature = "reexport-roxmltree")]
+pub use roxmltree;
+
+#[cfg(feature = "std")]
+pub use binary::BinaryMode;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+pub use borrowed::{BorrowedValue, XmlDocument};
+#[cfg(feature = "bson")]
+pub use bson::BsonSink;
+#[cfg(feature = "std")]
+pub use callback::ElementAction;
+#[cfg(feature = "std")]
+pub use cancel::{CancellationToken, Cancelled};
+#[cfg(feature = "std")]
+pub use config::ConfigError;
+#[cfg(feature = "std")]
+pub use dedup::SiblingDeduplication;
+#[cfg(feature = "std")]
+pub use diff::DiffEntry;
+#[cfg(feature = "std")]
+pub use duplicates::DuplicateAttributes;
+#[cfg(feature = "std")]
+pub use encoding::Utf8Policy;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+pub use events::JsonEvent;
+#[cfg(feature = "std")]
+pub use external::EntityResolver;
+#[cfg(feature = "std")]
+pub use feeds::feed_to_json;
+#[cfg(feature = "std")]
+pub use filter::FilterAction;
+#[cfg(feature = "figment")]
+pub use format::XmlConfigProvider;
+#[cfg(feature = "std")]
+pub use format::{ConfigLoadError, XmlConfigFormat};
+#[cfg(feature = "std")]
+pub use include::{FileResolver, IncludeResolver};
+#[cfg(feature = "std")]
+pub use incremental::Incremental;
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+pub use inference::{InferredRules, TypeInference};
+#[cfg(feature = "std")]
+pub use intern::{InternedValue, InterningSink};
+#[cfg(feature = "std")]
+pub use lazy::JsonFromXml;
+#[cfg(feature = "std")]
+pub use log_reader::XmlLogReader;
+#[cfg(feature = "std")]
+pub use merge::{ArrayMerge, MergeRules, ScalarMerge};
+#[cfg(feature = "mmap")]
+pub use mmap::MappedFile;
+#[cfg(feature = "msgpack")]
+pub use msgpack::MsgPackSink;
+#[cfg(feature = "std")]
+pub use profile::ConversionProfile;
+#[cfg(feature = "std")]
+pub use promote::PromotionConflict;
+#[cfg(feature = "std")]
+pub use report::{ConversionReport, Loss, LossKind, Warning, WarningKind};
+#[cfg(feature = "std")]
+pub use roundtrip::RoundTripReport;
+#[cfg(feature = "std")]
+pub use sink::{emit_value, JsonSink, ValueSink};
+#[cfg(feature = "std")]
+pub use spec::MappingSpec;
+#[cfg(feature = "std")]
+pub use stats::ConversionStats;
+#[cfg(feature = "std")]
+pub use trace::TraceEvent;
+#[cfg(feature = "std")]
+pub use transform::{MapValues, RemoveKey, RenameKey, SortKeys, StripPrefix, Transform};
+#[cfg(feature = "std")]
+pub use tree::XmlNode;
+#[cfg(feature = "std")]
+pub use types::{JsonType, NumberMode};
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+pub use visitor::{JsonVisitor, Visitor};
+#[cfg(feature = "web")]
+pub use web::{XmlJson, XmlJsonRejection};
+
+#[cfg(not(any(feature = "roxmltree", feature = "quick-xml")))]
+compile_error!("either feature \"roxmltree\" or \"quick-xml\" must be enabled");
+
+/// Defines how empty and `xsi:nil="true"` elements are converted, see `XmlToJson::with_null_policy`.
+/// Attribute values are always converted to strings.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg(feature = "std")]
+pub enum NullPolicy {
+    /// empty elements are skipped and `xsi:nil` is converted like any other attribute,
+    /// only an empty document is converted to null
+    #[default]
+    Skip,
+    /// elements with `xsi:nil="true"` are converted to null, other empty elements are skipped
+    XsiNil,
+    /// empty elements and elements with `xsi:nil="true"` are converted to null
+    Null,
+    /// null is never emitted, empty elements are converted to empty objects
+    Never,
+}
+
+/// Defines how namespaced element and attribute names are converted to keys,
+/// see `XmlToJson::with_namespace_mode`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg(feature = "std")]
+pub enum NamespaceMode {
+    /// use the local name only, e.g. `href` for `xlink:href`
+    #[default]
+    Strip,
+    /// use the prefix declared in the document, e.g. `xlink:href`
+    Prefix,
+    /// use the namespace URI in Clark notation, e.g. `{http://www.w3.org/1999/xlink}href`
+    Expanded,
+}
+
+/// Defines how `xmlns` declarations are converted, see `XmlToJson::with_namespace_declarations`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg(feature = "std")]
+pub enum NamespaceDeclarations {
+    /// convert them like attributes, e.g. `@xmlns:xlink`
+    Keep,
+    /// ignore them
+    #[default]
+    Drop,
+    /// collect them in a `#namespaces` object, keyed by prefix or `""` for the default namespace
+    Collect,
+}
+
+/// Defines how tabs and line breaks in attribute values are converted,
+/// see `XmlToJson::with_attribute_normalization`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg(feature = "std")]
+pub enum AttributeNormalization {
+    /// replaced by spaces as required by the XML spec, escaped ones like `&#10;` are kept
+    #[default]
+    Spec,
+    /// kept as written in the document
+    Raw,
+}
+
+/// Defines how repeated children are inlined if only some of them are simple,
+/// see `XmlToJson::with_inline_simple_children`
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg(feature = "std")]
+pub enum InlineConflict {
+    /// inline the simple ones, so the array mixes strings and objects
+    Mixed,
+    /// keep all of them as objects, so the array has a uniform type
+    KeepObjects,
+}
 
+/// Defines when child elements are converted to arrays, see `XmlToJson::with_array_mode`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg(feature = "std")]
+pub enum ArrayMode {
+    /// repeated children become an array, single children stay objects
+    #[default]
+    Auto,
+    /// every child becomes an array, also if it occurs only once
+    AlwaysWrapChildren,
+    /// fail if a child is repeated that isn't declared as array by `with_array_paths`,
+    /// `with_plural_names` or an element callback
+    ErrorOnUnexpectedRepeat,
+}
+
+/// A single key renaming rule, see `XmlToJson::with_key_rename`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg(feature = "std")]
+pub enum KeyRename {
+    /// rename every key that matches exactly, e.g. `#text` to `$text`
+    Key(String, String),
+    /// replace the prefix of every key that starts with it, e.g. `@href` to `_href`
+    Prefix(String, String),
+    /// rename only the key at the given path, e.g. `/a/b/#text`. Arrays don't add a path segment.
+    Path(String, String),
+}
+
+#[cfg(feature = "std")]
+impl KeyRename {
+    /// rename every key `from` to `to`
+    pub fn key(from: &str, to: &str) -> Self {
+        KeyRename::Key(from.into(), to.into())
+    }
+    /// replace prefix `from` of all keys with `to`
+    pub fn prefix(from: &str, to: &str) -> Self {
+        KeyRename::Prefix(from.into(), to.into())
+    }
+    /// rename the last key of `path` to `to`
+    pub fn path(path: &str, to: &str) -> Self {
+        KeyRename::Path(path.into(), to.into())
+    }
+
+    fn apply(&self, key: &str, path: &str) -> Option<String> {
+        match self {
+            KeyRename::Key(from, to) if key == from => Some(to.clone()),
+            KeyRename::Prefix(from, to) => key
+                .strip_prefix(from.as_str())
+                .map(|rest| [to.as_str(), rest].concat()),
+            KeyRename::Path(from, to) if path == from => Some(to.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 pub struct XmlToJson {
     with_root: bool,
     text_name: String,
     attribute_prefix: String,
+    text_names: Vec<(String, String)>,
+    key_renames: Vec<KeyRename>,
+    inherited_attributes: Vec<String>,
+    flatten_text_arrays: bool,
+    strict: bool,
+    loss_callback: Option<LossCallback>,
+    binary_elements: Vec<String>,
+    binary_mode: BinaryMode,
+    binary_length_only: bool,
+    max_convert_depth: Option<usize>,
+    max_nesting_depth: usize,
+    with_declaration: bool,
+    allow_dtd: bool,
+    merge_rules: MergeRules,
+    plural_names: Vec<(String, String)>,
+    include_resolver: Option<Box<dyn IncludeResolver + Send + Sync>>,
+    null_policy: NullPolicy,
+    html_entities: bool,
+    custom_entities: HashMap<String, String>,
+    max_output_bytes: Option<usize>,
+    namespace_mode: NamespaceMode,
+    canonicalize: bool,
+    type_overrides: HashMap<String, JsonType>,
+    duplicate_attributes: Option<DuplicateAttributes>,
+    compact_attribute_elements: Option<Vec<String>>,
+    compact_attributes_key: Option<String>,
+    case_insensitive_keys: bool,
+    original_names: bool,
+    cancellation: Option<CancellationToken>,
+    deadline: Option<std::time::Duration>,
+    attribute_order: Vec<String>,
+    source_spans: bool,
+    sanitize_input: bool,
+    skip_leading_junk: bool,
+    number_mode: NumberMode,
+    namespace_declarations: NamespaceDeclarations,
+    unknown_entities_as_text: bool,
+    sibling_index: bool,
+    max_attributes: Option<usize>,
+    max_name_length: Option<usize>,
+    stripped_namespaces: Vec<String>,
+    output_template: Vec<(String, String)>,
+    key_separator: String,
+    csv_delimiter: char,
+    key_filters: Vec<(String, FilterAction)>,
+    entity_resolver: Option<Box<dyn EntityResolver + Send + Sync>>,
+    preserve_attribute_whitespace: bool,
+    attribute_normalization: AttributeNormalization,
+    flatten_separator: Option<String>,
+    inline_simple_children: Option<InlineConflict>,
+    utf8_policy: Utf8Policy,
+    directives: bool,
+    datetime_names: Option<Vec<String>>,
+    sibling_deduplication: Option<SiblingDeduplication>,
+    element_callback: Option<ElementCallback>,
+    promoted_attributes: Vec<String>,
+    promotion_conflict: PromotionConflict,
+    transforms: Vec<Box<dyn Transform>>,
+    array_paths: Vec<String>,
+    preserve_attribute_order: bool,
+    array_mode: ArrayMode,
+    presence_attributes: Vec<String>,
+    trace: Option<TraceCallback>,
+    large_text_handler: Option<(usize, LargeTextHandler)>,
+}
+
+#[cfg(feature = "std")]
+type LossCallback = Box<dyn Fn(&Loss) + Send + Sync>;
+#[cfg(feature = "std")]
+type ElementCallback = Box<dyn Fn(&str, &str, &[(&str, &str)]) -> ElementAction + Send + Sync>;
+#[cfg(feature = "std")]
+type TraceCallback = Box<dyn Fn(&TraceEvent) + Send + Sync>;
+#[cfg(feature = "std")]
+type LargeTextHandler = Box<dyn Fn(&str, &mut dyn std::io::Read) -> Value + Send + Sync>;
+
+/// mutable state while traversing a document
+#[derive(Default)]
+#[cfg(feature = "std")]
+struct Context {
+    check_losses: bool,
+    depth: usize,
+    path: String,
+    inherited: Map<String, Value>,
+    includes: Vec<String>,
+    output_bytes: usize,
+    duplicate_attributes: duplicates::DuplicateTable,
+    deadline: Option<std::time::Instant>,
+    report: ConversionReport,
+    stats: Option<ConversionStats>,
+    elements: usize,
+}
+
+#[cfg(feature = "std")]
+impl Context {
+    fn enter(&mut self, name: &str) -> usize {
+        let len = self.path.len();
+        self.path.push('/');
+        self.path.push_str(name);
+        self.depth += 1;
+        len
+    }
+
+    fn leave(&mut self, len: usize) {
+        self.path.truncate(len);
+        self.depth -= 1;
+    }
 }
 
+#[cfg(feature = "std")]
 impl Default for XmlToJson {
     fn default() -> Self {
         XmlToJson {
             with_root: false,
             text_name: "#text".to_string(),
             attribute_prefix: "@".to_string(), // you can't serialize it again to XML when changing this
+            text_names: Vec::new(),
+            key_renames: Vec::new(),
+            inherited_attributes: Vec::new(),
+            flatten_text_arrays: false,
+            strict: false,
+            loss_callback: None,
+            binary_elements: Vec::new(),
+            binary_mode: BinaryMode::Base64,
+            binary_length_only: false,
+            max_convert_depth: None,
+            max_nesting_depth: depth::DEFAULT_MAX_NESTING_DEPTH,
+            with_declaration: false,
+            allow_dtd: false,
+            merge_rules: MergeRules::default(),
+            plural_names: Vec::new(),
+            include_resolver: None,
+            null_policy: NullPolicy::default(),
+            html_entities: false,
+            custom_entities: HashMap::new(),
+            max_output_bytes: None,
+            namespace_mode: NamespaceMode::default(),
+            canonicalize: false,
+            type_overrides: HashMap::new(),
+            duplicate_attributes: None,
+            compact_attribute_elements: None,
+            compact_attributes_key: None,
+            case_insensitive_keys: false,
+            original_names: false,
+            cancellation: None,
+            deadline: None,
+            attribute_order: Vec::new(),
+            source_spans: false,
+            sanitize_input: false,
+            skip_leading_junk: false,
+            number_mode: NumberMode::default(),
+            namespace_declarations: NamespaceDeclarations::default(),
+            unknown_entities_as_text: false,
+            sibling_index: false,
+            max_attributes: None,
+            max_name_length: None,
+            stripped_namespaces: Vec::new(),
+            output_template: Vec::new(),
+            key_separator: ".".to_string(),
+            csv_delimiter: ',',
+            key_filters: Vec::new(),
+            entity_resolver: None,
+            preserve_attribute_whitespace: false,
+            attribute_normalization: AttributeNormalization::Spec,
+            flatten_separator: None,
+            inline_simple_children: None,
+            utf8_policy: Utf8Policy::Error,
+            directives: false,
+            datetime_names: None,
+            sibling_deduplication: None,
+            element_callback: None,
+            promoted_attributes: Vec::new(),
+            promotion_conflict: PromotionConflict::KeepPrefix,
+            transforms: Vec::new(),
+            array_paths: Vec::new(),
+            preserve_attribute_order: false,
+            array_mode: ArrayMode::default(),
+            presence_attributes: Vec::new(),
+            trace: None,
+            large_text_handler: None,
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl XmlToJson {
     /// Parse XML string and return serde_json Value
+    #[cfg(feature = "roxmltree")]
+    pub fn xml_to_json(&self, xml: &str) -> Result<Value, Box<dyn std::error::Error>> {
+        let check_losses = self.strict || self.loss_callback.is_some();
+        Ok(self.convert(xml, check_losses)?.0)
+    }
+
+    /// Parse XML string and return serde_json Value
+    #[cfg(not(feature = "roxmltree"))]
     pub fn xml_to_json(&self, xml: &str) -> Result<Value, Box<dyn std::error::Error>> {
-        let doc = roxmltree::Document::parse(xml)?;
-        let root = doc.root_element();
-        Ok(self.parse_root(&root).unwrap_or(Value::Null))
+        self.xml_to_json_with_quick_xml(xml)
+    }
+
+    /// Parse XML bytes and return serde_json Value. The encoding is detected by BOM or
+    /// XML declaration, UTF-8 input is converted without copying it first.
+    pub fn xml_to_json_bytes(&self, bytes: &[u8]) -> Result<Value, Box<dyn std::error::Error>> {
+        let xml = encoding::decode_xml(bytes, self.utf8_policy, &mut Vec::new())?;
+        self.xml_to_json(&xml)
+    }
+
+    /// Parse XML bytes like `xml_to_json_bytes` and return a report, which contains a
+    /// warning with the byte offset of each replaced or skipped invalid UTF-8 sequence
+    #[cfg(feature = "roxmltree")]
+    pub fn xml_to_json_bytes_with_report(
+        &self,
+        bytes: &[u8],
+    ) -> Result<(Value, ConversionReport), Box<dyn std::error::Error>> {
+        let mut invalid = Vec::new();
+        let xml = encoding::decode_xml(bytes, self.utf8_policy, &mut invalid)?;
+        let (value, mut report) = self.convert(&xml, true)?;
+        let warnings = invalid.into_iter().map(|offset| Warning {
+            path: String::new(),
+            kind: WarningKind::InvalidUtf8(offset),
+        });
+        report.warnings.splice(0..0, warnings);
+        Ok((value, report))
+    }
+
+    /// set how invalid UTF-8 in byte input is handled, default is `Utf8Policy::Error`
+    pub fn with_utf8_policy(mut self, policy: Utf8Policy) -> Self {
+        self.utf8_policy = policy;
+        self
     }
 
     /// parse with XML root, default is false as quick-xml usually doesn't parse the root
@@ -35,88 +574,621 @@ impl XmlToJson {
         self.with_root = true;
         self
     }
+
     /// change name of inner XML value
     pub fn with_text_name(mut self, text_name: &str) -> Self {
         self.text_name = String::from(text_name);
         self
     }
 
+    /// use different text names for specific elements, e.g. `&[("script", "#script_text")]`
+    pub fn with_text_name_for(mut self, names: &[(&str, &str)]) -> Self {
+        self.text_names = names
+            .iter()
+            .map(|(element, text_name)| (element.to_string(), text_name.to_string()))
+            .collect();
+        self
+    }
+
+    /// change the prefix of attribute keys, default is `@`.
+    /// quick-xml can only serialize the JSON back to XML with the `@` prefix.
+    pub fn with_attribute_prefix(mut self, prefix: &str) -> Self {
+        self.attribute_prefix = String::from(prefix);
+        self
+    }
+
+    /// add a key renaming rule that is applied after conversion, rules are applied in order
+    pub fn with_key_rename(mut self, rename: KeyRename) -> Self {
+        self.key_renames.push(rename);
+        self
+    }
+
+    /// propagate inheritable attributes like `xml:lang` or `xml:base` to all descendant objects
+    /// that don't define them themselves
+    pub fn with_inherited_attributes(mut self, names: &[&str]) -> Self {
+        self.inherited_attributes = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// convert arrays that only contain pure text nodes into arrays of strings,
+    /// e.g. `{"d": ["D", "1"]}` instead of `{"d": [{"#text": "D"}, {"#text": "1"}]}`
+    pub fn with_flatten_text_arrays(mut self) -> Self {
+        self.flatten_text_arrays = true;
+        self
+    }
+
+    /// convert children that only contain text to their text, e.g. `{"name": "Ann"}` instead
+    /// of `{"name": {"#text": "Ann"}}`. `conflict` defines how arrays are converted where only
+    /// some of the repeated children are simple.
+    pub fn with_inline_simple_children(mut self, conflict: InlineConflict) -> Self {
+        self.inline_simple_children = Some(conflict);
+        self
+    }
+
+    /// keep leading and trailing whitespace of attribute values, element texts are still trimmed
+    pub fn with_preserve_attribute_whitespace(mut self) -> Self {
+        self.preserve_attribute_whitespace = true;
+        self
+    }
+
+    /// record the original order of attributes as `#attr_order`, e.g. `["href", "class"]`,
+    /// if an element has more than one. `json_to_xml_pretty` writes attributes in this order.
+    pub fn with_preserve_attribute_order(mut self) -> Self {
+        self.preserve_attribute_order = true;
+        self
+    }
+
+    /// keep tabs and line breaks of attribute values instead of replacing them by spaces.
+    /// XML written from JSON escapes them, so they survive a round trip.
+    pub fn with_attribute_normalization(mut self, normalization: AttributeNormalization) -> Self {
+        self.attribute_normalization = normalization;
+        self
+    }
+
+    /// fail instead of silently dropping information, see `ConversionReport` for
+    /// the kind of losses that are detected
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// call `callback` for every place where information is lost during conversion
+    pub fn with_loss_callback(mut self, callback: impl Fn(&Loss) + Send + Sync + 'static) -> Self {
+        self.loss_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// validate and normalize the text of the given elements as base64 or hex encoded binary data,
+    /// conversion fails if the content isn't valid
+    pub fn with_binary_elements(mut self, names: &[&str], mode: BinaryMode) -> Self {
+        self.binary_elements = names.iter().map(|name| name.to_string()).collect();
+        self.binary_mode = mode;
+        self
+    }
+
+    /// replace the text of binary elements by its decoded length in `#byte_length`
+    pub fn with_binary_length_only(mut self) -> Self {
+        self.binary_length_only = true;
+        self
+    }
+
+    /// only convert elements up to depth `max_depth`, where the root element has depth 0.
+    /// Deeper elements are embedded verbatim as XML string under a `#raw` key.
+    pub fn with_max_convert_depth(mut self, max_depth: usize) -> Self {
+        self.max_convert_depth = Some(max_depth);
+        self
+    }
+
+    /// add version, encoding and standalone of the XML declaration and the DOCTYPE name
+    /// under a `#declaration` key, so that an equivalent XML header can be written later
+    pub fn with_declaration(mut self) -> Self {
+        self.with_declaration = true;
+        self
+    }
+
+    /// allow documents with a DOCTYPE declaration, internal entities are expanded.
+    /// Default is false, as a DTD is rarely needed and a possible attack surface.
+    pub fn with_dtd(mut self) -> Self {
+        self.allow_dtd = true;
+        self
+    }
+
+    /// set the rules used by `merge_xml_documents`
+    pub fn with_merge_rules(mut self, rules: MergeRules) -> Self {
+        self.merge_rules = rules;
+        self
+    }
+
+    /// collect children with a singular name like `item` into an array under a plural key
+    /// like `items`, even if there is only one child
+    pub fn with_plural_names(mut self, names: &[(&str, &str)]) -> Self {
+        self.plural_names = names
+            .iter()
+            .map(|(singular, plural)| (singular.to_string(), plural.to_string()))
+            .collect();
+        self
+    }
+
+    /// resolve `<xi:include href="…"/>` elements with `resolver` and convert the included
+    /// documents as part of the including document
+    pub fn with_include_resolver(
+        mut self,
+        resolver: impl IncludeResolver + Send + Sync + 'static,
+    ) -> Self {
+        self.include_resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// set how empty and `xsi:nil` elements are converted, default is `NullPolicy::Skip`
+    pub fn with_null_policy(mut self, policy: NullPolicy) -> Self {
+        self.null_policy = policy;
+        self
+    }
+
+    /// resolve common HTML entities like `&nbsp;` or `&auml;` before parsing,
+    /// which are otherwise rejected as they are not predefined by XML
+    pub fn with_html_entities(mut self) -> Self {
+        self.html_entities = true;
+        self
+    }
+
+    /// resolve the given named entities before parsing, the replacement text is escaped
+    pub fn with_entities(mut self, entities: HashMap<String, String>) -> Self {
+        self.custom_entities = entities;
+        self
+    }
+
+    /// keep unknown entities and stray `&` as text instead of failing, each one is reported
+    /// as warning by `xml_to_json_with_report`
+    pub fn with_unknown_entities_as_text(mut self) -> Self {
+        self.unknown_entities_as_text = true;
+        self
+    }
+
+    /// stop converting once the estimated size of the serialized JSON exceeds `max_bytes`.
+    /// Objects with skipped children get a `#truncated: true` marker.
+    pub fn with_max_output_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_output_bytes = Some(max_bytes);
+        self
+    }
+
+    /// reject elements with more than `max` attributes
+    pub fn with_max_attributes_per_element(mut self, max: usize) -> Self {
+        self.max_attributes = Some(max);
+        self
+    }
+
+    /// reject element and attribute names longer than `max` bytes
+    pub fn with_max_name_length(mut self, max: usize) -> Self {
+        self.max_name_length = Some(max);
+        self
+    }
+
+    /// set how namespaced element and attribute names are converted, default is
+    /// `NamespaceMode::Strip`
+    pub fn with_namespace_mode(mut self, mode: NamespaceMode) -> Self {
+        self.namespace_mode = mode;
+        self
+    }
+
+    /// define how `xmlns` declarations are converted, default is `NamespaceDeclarations::Drop`
+    pub fn with_namespace_declarations(mut self, mode: NamespaceDeclarations) -> Self {
+        self.namespace_declarations = mode;
+        self
+    }
+
+    /// drop all elements and attributes of the namespaces `uris`, e.g. the
+    /// `http://www.w3.org/2001/XMLSchema-instance` attributes of SOAP payloads
+    pub fn with_strip_namespaces(mut self, uris: &[&str]) -> Self {
+        self.stripped_namespaces = uris.iter().map(|uri| uri.to_string()).collect();
+        self
+    }
+
+    /// canonicalize the input, so that semantically identical XML produces identical JSON.
+    /// Whitespace-only text is dropped instead of converted to an empty text and
+    /// CDATA sections and text are merged. Attribute order never matters, as keys are sorted.
+    /// Combine with `NamespaceMode::Expanded` to also ignore differing namespace prefixes.
+    pub fn with_canonicalization(mut self) -> Self {
+        self.canonicalize = true;
+        self
+    }
+
+    /// convert the text of elements or values of attributes at the given XML paths into
+    /// the given type, e.g. `/order/total` or `/order/@id`. Conversion fails if a value
+    /// can't be converted.
+    pub fn with_type_overrides(mut self, overrides: HashMap<String, JsonType>) -> Self {
+        self.type_overrides = overrides;
+        self
+    }
+
+    /// always convert the elements at the given XML paths into arrays, e.g. `/order/item`,
+    /// also if they aren't repeated. See `TypeInference` to learn them from sample documents.
+    pub fn with_array_paths(mut self, paths: &[&str]) -> Self {
+        self.array_paths = paths.iter().map(|path| path.to_string()).collect();
+        self
+    }
+
+    /// set when child elements become arrays independent of the element counts in a document,
+    /// default is `ArrayMode::Auto`
+    pub fn with_array_mode(mut self, mode: ArrayMode) -> Self {
+        self.array_mode = mode;
+        self
+    }
+
+    /// define how numbers of type overrides that exceed the `f64` precision are converted,
+    /// default is `NumberMode::Lossy`
+    pub fn with_number_mode(mut self, mode: NumberMode) -> Self {
+        self.number_mode = mode;
+        self
+    }
+
+    /// tolerate illegally repeated attributes, which are otherwise rejected by the parser
+    pub fn with_duplicate_attributes(mut self, policy: DuplicateAttributes) -> Self {
+        self.duplicate_attributes = Some(policy);
+        self
+    }
+
+    /// convert elements that only contain attributes, like `<img src="a.png"/>`, without the
+    /// attribute prefix: `{"img": {"src": "a.png"}}`. Applies to all elements if `names` is empty.
+    pub fn with_compact_attribute_elements(mut self, names: &[&str]) -> Self {
+        self.compact_attribute_elements = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// nest the attributes of compacted elements into an object named `key`,
+    /// see `with_compact_attribute_elements`
+    pub fn with_compact_attributes_key(mut self, key: &str) -> Self {
+        self.compact_attributes_key = Some(String::from(key));
+        self
+    }
+
+    /// convert element names to lowercase, so that siblings like `<Item>` and `<item>` are
+    /// grouped into the same array
+    pub fn with_case_insensitive_keys(mut self) -> Self {
+        self.case_insensitive_keys = true;
+        self
+    }
+
+    /// record the original name of elements renamed by `with_case_insensitive_keys`
+    /// as `#originalName`
+    pub fn with_original_names(mut self) -> Self {
+        self.original_names = true;
+        self
+    }
+
+    /// add the location of each element in the input as `#span` with byte offsets `start` and
+    /// `end` and the 1-based `line` and `col`. Offsets refer to the input after entity
+    /// expansion and are only available with the roxmltree backend.
+    pub fn with_source_spans(mut self) -> Self {
+        self.source_spans = true;
+        self
+    }
+
+    /// ignore a UTF-8 BOM and whitespace in front of the XML, which often come with input from
+    /// HTTP or Windows files. With `skip_junk`, anything in front of the first `<` is ignored.
+    pub fn with_input_sanitizing(mut self, skip_junk: bool) -> Self {
+        self.sanitize_input = true;
+        self.skip_leading_junk = skip_junk;
+        self
+    }
+
+    /// add the position of each element among its sibling elements as `#index`, so that the
+    /// document order of differently named siblings can be restored
+    pub fn with_sibling_index(mut self) -> Self {
+        self.sibling_index = true;
+        self
+    }
+
     /// Renames #text into $text, so that the JSON can be used by quick-xml
-    pub fn prepare_for_quick_xml(self, input: Value) -> Value {
-        Self::rename_keys(input, &self.text_name, "$text")
+    pub fn prepare_for_quick_xml(&self, input: Value) -> Value {
+        Self::apply_renames(
+            input,
+            &[KeyRename::key(&self.text_name, "$text")],
+            &mut String::new(),
+        )
     }
 
-    // You may need to rename #text to $text to serialize it again to xml
-    pub fn rename_keys(input: Value, old_key: &str, new_key: &str) -> Value {
-        match input {
-            Value::Object(mut map_string_val) => {
-                if let Some(val) = map_string_val.remove(old_key) {
-                    map_string_val.insert(new_key.into(), val);
+    /// Serialize a converted JSON value back to XML with quick-xml, see `prepare_for_quick_xml`.
+    /// If `root_name` is empty, `value` has to contain the root element, like the output of
+    /// `with_root`.
+    #[cfg(feature = "quick-xml")]
+    pub fn to_quick_xml_string(
+        &self,
+        value: Value,
+        root_name: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let (root_name, value) = match value {
+            Value::Object(map) if root_name.is_empty() => {
+                let mut entries = map.into_iter();
+                match (entries.next(), entries.next()) {
+                    (Some(root), None) => root,
+                    _ => return Err("value must contain exactly one root element".into()),
                 }
-                map_string_val
-                    .iter_mut()
-                    .for_each(|val| *val.1 = Self::rename_keys(val.1.take(), old_key, new_key));
-                map_string_val.into()
-            }
-            Value::Array(mut array_val) => {
-                array_val
-                    .iter_mut()
-                    .for_each(|val| *val = Self::rename_keys(val.take(), old_key, new_key));
-                array_val.into()
             }
+            _ if root_name.is_empty() => return Err("value must contain the root element".into()),
+            value => (root_name.to_string(), value),
+        };
+        let value = self.prepare_for_quick_xml(value);
+        Ok(quick_xml::se::to_string_with_root(&root_name, &value)?)
+    }
+
+    /// Apply the configured key renaming rules and transforms to a JSON value
+    pub fn rename_keys(&self, input: Value) -> Value {
+        let renamed = if self.key_renames.is_empty() {
+            input
+        } else {
+            Self::apply_renames(input, &self.key_renames, &mut String::new())
+        };
+        self.transforms
+            .iter()
+            .fold(renamed, |value, transform| transform.apply(value))
+    }
+
+    pub(crate) fn apply_renames(input: Value, renames: &[KeyRename], path: &mut String) -> Value {
+        match input {
+            Value::Object(map_string_val) => map_string_val
+                .into_iter()
+                .map(|(key, val)| {
+                    let path_len = path.len();
+                    path.push('/');
+                    path.push_str(&key);
+                    let new_key = renames
+                        .iter()
+                        .fold(key, |key, rename| rename.apply(&key, path).unwrap_or(key));
+                    let new_val = Self::apply_renames(val, renames, path);
+                    path.truncate(path_len);
+                    (new_key, new_val)
+                })
+                .collect::<Map<String, Value>>()
+                .into(),
+            Value::Array(array_val) => array_val
+                .into_iter()
+                .map(|val| Self::apply_renames(val, renames, path))
+                .collect::<Vec<Value>>()
+                .into(),
             other => other,
         }
     }
 
-    fn parse_root(&self, node: &roxmltree::Node) -> Option<Value> {
-        if self.with_root {
-            let mut root = Map::new();
-            root.insert(
-                node.tag_name().name().to_string(),
-                self.parse_node(node).unwrap_or(Value::Null),
-            );
-            Some(Value::Object(root))
+    /// apply all configured text transformations in front of parsing
+    /// strip a BOM and whitespace, or any junk, in front of the first markup
+    fn sanitize_input<'a>(&self, xml: &'a str) -> &'a str {
+        if !self.sanitize_input {
+            return xml;
+        }
+        let xml = xml.trim_start_matches('\u{feff}').trim_start();
+        match xml.find('<') {
+            Some(start) if self.skip_leading_junk => &xml[start..],
+            _ => xml,
+        }
+    }
+
+    fn preprocess<'a>(
+        &self,
+        xml: &'a str,
+        ctx: &mut Context,
+    ) -> Result<Cow<'a, str>, Box<dyn std::error::Error>> {
+        let xml = match self.resolve_external(self.sanitize_input(xml))? {
+            Cow::Borrowed(xml) => self.resolve_entities(xml, ctx),
+            Cow::Owned(xml) => match self.resolve_entities(&xml, ctx) {
+                Cow::Borrowed(_) => Cow::Owned(xml),
+                Cow::Owned(resolved) => Cow::Owned(resolved),
+            },
+        };
+        self.check_nesting_depth(&xml)?;
+        Ok(match self.duplicate_attributes {
+            Some(policy) => match policy.rewrite(&xml, &mut ctx.duplicate_attributes) {
+                Cow::Borrowed(_) => xml,
+                Cow::Owned(rewritten) => Cow::Owned(rewritten),
+            },
+            None => xml,
+        })
+    }
+
+    fn record_loss(&self, ctx: &mut Context, kind: LossKind) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(path = ctx.path, "{kind}");
+        let loss = Loss {
+            path: ctx.path.clone(),
+            kind,
+        };
+        if let Some(callback) = &self.loss_callback {
+            callback(&loss);
+        }
+        ctx.report.losses.push(loss);
+    }
+
+    /// check the limits of untrusted input for an element, `names` are the element name
+    /// followed by all attribute names
+    fn validate_element<'a>(
+        &self,
+        ctx: &Context,
+        attribute_count: usize,
+        mut names: impl Iterator<Item = &'a str>,
+    ) -> Result<(), String> {
+        if self.max_attributes.is_some_and(|max| attribute_count > max) {
+            return Err(format!("too many attributes at '{}'", ctx.path));
+        }
+        match names.find(|name| {
+            self.max_name_length.is_some_and(|max| name.len() > max)
+                || name.chars().any(char::is_control)
+        }) {
+            Some(name) => Err(format!("invalid name '{name:.32}' at '{}'", ctx.path)),
+            None => Ok(()),
+        }
+    }
+
+    fn record_warning(&self, ctx: &mut Context, kind: WarningKind) {
+        let warning = Warning {
+            path: ctx.path.clone(),
+            kind,
+        };
+        self.trace(TraceEvent::Warning(&warning));
+        ctx.report.warnings.push(warning);
+    }
+
+    fn set_sibling_index(&self, child_val: &mut Value, index: usize) {
+        if let (true, Value::Object(child)) = (self.sibling_index, child_val) {
+            child.insert("#index".into(), index.into());
+        }
+    }
+
+    /// insert a child element of the element at `ctx.path`, `array` if it is declared as array.
+    /// Fails on a repeated child with `ArrayMode::ErrorOnUnexpectedRepeat`.
+    fn insert_child(
+        &self,
+        elements: &mut Map<String, Value>,
+        name: &str,
+        mut child_val: Value,
+        array: bool,
+        ctx: &Context,
+    ) -> Result<(), String> {
+        let lowercase;
+        let name = if self.case_insensitive_keys && name.chars().any(char::is_uppercase) {
+            lowercase = name.to_lowercase();
+            if let (true, Value::Object(child)) = (self.original_names, &mut child_val) {
+                child.insert("#originalName".into(), Value::String(name.into()));
+            }
+            lowercase.as_str()
         } else {
-            self.parse_node(node)
-        }
-    }
-
-    fn parse_node(&self, node: &roxmltree::Node) -> Option<Value> {
-        let mut elements = Map::new();
-        if let Some(text) = node.text() {
-            elements.insert(self.text_name.clone(), Value::String(text.trim().into()));
-        }
-        for attr in node.attributes() {
-            let key = [&self.attribute_prefix, attr.name()].concat();
-            let val = attr.value().trim().into();
-            elements.insert(key, Value::String(val));
-        }
-        for child in node.children() {
-            let name = child.tag_name().name().to_string();
-            if !name.is_empty() {
-                if let Some(child_val) = self.parse_node(&child) {
-                    // check if val already exists
-                    if let Some(found) = elements.get_mut(&name) {
-                        if let Some(array) = found.as_array_mut() {
-                            array.push(child_val);
-                        } else {
-                            // remove old value and convert to array
-                            let new_val = match elements.remove(&name) {
-                                None => vec![child_val],
-                                Some(old_val) => vec![old_val, child_val],
-                            };
-                            elements.insert(name, Value::Array(new_val));
-                        }
-                    } else {
-                        elements.insert(name, child_val);
-                    }
+            name
+        };
+        if let Some((_, plural)) = self
+            .plural_names
+            .iter()
+            .find(|(singular, _)| *singular == name)
+        {
+            match elements.get_mut(plural) {
+                Some(Value::Array(array)) => array.push(child_val),
+                _ => {
+                    elements.insert(plural.clone(), Value::Array(vec![child_val]));
                 }
             }
+            return Ok(());
         }
-        if elements.is_empty() {
-            None
+        // check if val already exists, the key is only allocated for the first occurrence
+        if let Some(found) = elements.get_mut(name) {
+            if let Some(array) = found.as_array_mut() {
+                array.push(child_val);
+            } else if self.array_mode == ArrayMode::ErrorOnUnexpectedRepeat {
+                return Err(format!(
+                    "unexpected repeated element '{name}' at '{}'",
+                    ctx.path
+                ));
+            } else {
+                let path = &ctx.path;
+                self.trace(TraceEvent::ArrayPromoted { path, name });
+                // convert old value to array
+                *found = Value::Array(vec![found.take(), child_val]);
+            }
+        } else if array || self.array_mode == ArrayMode::AlwaysWrapChildren {
+            elements.insert(name.to_string(), Value::Array(vec![child_val]));
         } else {
-            Some(Value::Object(elements))
+            elements.insert(name.to_string(), child_val);
+        }
+        Ok(())
+    }
+
+    /// insert the declaration of namespace `prefix`, or the default namespace if it is None
+    fn insert_namespace_declaration(
+        &self,
+        elements: &mut Map<String, Value>,
+        prefix: Option<&str>,
+        uri: &str,
+    ) {
+        match self.namespace_declarations {
+            NamespaceDeclarations::Keep => {
+                let key = match prefix {
+                    Some(prefix) => [&self.attribute_prefix, "xmlns:", prefix].concat(),
+                    None => [&self.attribute_prefix, "xmlns"].concat(),
+                };
+                elements.insert(key, Value::String(uri.into()));
+            }
+            NamespaceDeclarations::Collect => {
+                if let Some(namespaces) = elements
+                    .entry("#namespaces")
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                {
+                    namespaces.insert(prefix.unwrap_or_default().into(), Value::String(uri.into()));
+                }
+            }
+            NamespaceDeclarations::Drop => {}
+        }
+    }
+
+    /// remove the attribute prefix of an element that only contains attributes
+    fn compact_attributes(&self, name: &str, elements: Map<String, Value>) -> Map<String, Value> {
+        match &self.compact_attribute_elements {
+            Some(names) if names.is_empty() || names.iter().any(|compact| compact == name) => {}
+            _ => return elements,
+        }
+        let attributes = elements
+            .into_iter()
+            .map(
+                |(key, val)| match key.strip_prefix(self.attribute_prefix.as_str()) {
+                    Some(name) => (name.to_string(), val),
+                    None => (key, val),
+                },
+            )
+            .collect();
+        match &self.compact_attributes_key {
+            Some(key) => Map::from_iter([(key.clone(), Value::Object(attributes))]),
+            None => attributes,
+        }
+    }
+
+    fn flatten_text_arrays(&self, elements: &mut Map<String, Value>) {
+        let inline = self.inline_simple_children;
+        for (name, val) in elements.iter_mut() {
+            let text_name = self.text_name_for(name);
+            let is_text_only = |val: &Value| {
+                val.as_object()
+                    .is_some_and(|obj| obj.len() == 1 && obj.contains_key(text_name))
+            };
+            let inline_text = |val: &mut Value| {
+                if let Some(text) = val.as_object_mut().and_then(|obj| obj.remove(text_name)) {
+                    *val = text;
+                }
+            };
+            if let Some(array) = val.as_array_mut() {
+                if array.iter().all(is_text_only) || inline == Some(InlineConflict::Mixed) {
+                    for val in array.iter_mut().filter(|val| is_text_only(val)) {
+                        inline_text(val);
+                    }
+                }
+            } else if inline.is_some() && is_text_only(val) {
+                inline_text(val);
+            }
+        }
+    }
+
+    /// an attribute value as converted, trimmed unless whitespace is preserved
+    fn attribute_value<'a>(&self, value: &'a str) -> &'a str {
+        match self.preserve_attribute_whitespace {
+            true => value,
+            false => value.trim(),
+        }
+    }
+
+    /// the text name of elements named `name`, see `with_text_name_for`
+    fn text_name_for(&self, name: &str) -> &str {
+        self.text_names
+            .iter()
+            .find(|(element, _)| element == name)
+            .map_or(&self.text_name, |(_, text_name)| text_name)
+    }
+
+    /// returns `text` converted according to the type overrides of `path`
+    fn typed_value(&self, path: &str, text: &str) -> Result<Value, Box<dyn std::error::Error>> {
+        match self.type_overrides.get(path) {
+            Some(json_type) => Ok(json_type
+                .convert(text, self.number_mode)
+                .map_err(|err| format!("{err} at '{path}'"))?),
+            None => match self.normalized_datetime(path, text) {
+                Some(datetime) => Ok(Value::String(datetime)),
+                None => Ok(Value::String(text.into())),
+            },
         }
     }
 }
@@ -186,12 +1258,912 @@ fn test_extended_xml_to_json() {
     );
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_serde_xml_to_json_to_xml() {
     let xml =
         "<a><b href=\"#self\">simple</b><b><c class=\"my_class\"><d>D</d><d>1</d></c></b></a>";
     let parser = XmlToJson::default();
     let json_value = parser.xml_to_json(xml).unwrap();
-    let comp_value = parser.prepare_for_quick_xml(json_value);
+    let comp_value = parser.prepare_for_quick_xml(json_value.clone());
     assert_eq!(xml, quick_xml::se::to_string_with_root("a", &comp_value).unwrap());
+    #[cfg(feature = "quick-xml")]
+    {
+        assert_eq!(parser.to_quick_xml_string(json_value, "a").unwrap(), xml);
+        let parser = XmlToJson::default().with_root();
+        let json_value = parser.xml_to_json(xml).unwrap();
+        assert_eq!(parser.to_quick_xml_string(json_value, "").unwrap(), xml);
+        assert!(XmlToJson::default()
+            .to_quick_xml_string(Value::Null, "")
+            .is_err());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_rename_keys() {
+    use serde_json::json;
+
+    let xml = "<a><b href=\"#self\">simple</b><c><b>inner</b></c></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_key_rename(KeyRename::prefix("@", "_"))
+            .with_key_rename(KeyRename::path("/c/b/#text", "value"))
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b": { "_href": "#self", "#text": "simple" }, "c": { "b": { "value": "inner" } } })
+    );
+    let parser = XmlToJson::default().with_key_rename(KeyRename::key("#text", "text"));
+    assert_eq!(
+        parser.rename_keys(json!([{ "#text": "x" }, { "b": { "#text": "y" } }])),
+        json!([{ "text": "x" }, { "b": { "text": "y" } }])
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_inherited_attributes() {
+    use serde_json::json;
+
+    let xml = "<a xml:lang=\"de\"><b>Hallo</b><b xml:lang=\"en\"><c>Hello</c></b></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_inherited_attributes(&["xml:lang"])
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "@lang": "de", "b": [
+            { "@lang": "de", "#text": "Hallo" },
+            { "@lang": "en", "c": { "@lang": "en", "#text": "Hello" } }
+        ] })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_flatten_text_arrays() {
+    use serde_json::json;
+
+    let xml = "<a><b><d>D</d><d>1</d></b><c>x</c><c y=\"z\">x</c></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_flatten_text_arrays()
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b": { "d": ["D", "1"] }, "c": [{ "#text": "x" }, { "@y": "z", "#text": "x" }] })
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_conversion_report() {
+    let xml = "<?pi x?><a xmlns:x=\"urn:x\"><!-- c --><x:b> simple </x:b><c/><b>1</b>tail</a>";
+    let (_, report) = XmlToJson::default().xml_to_json_with_report(xml).unwrap();
+    let kinds: Vec<LossKind> = report.losses.into_iter().map(|loss| loss.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            LossKind::ProcessingInstruction("pi".into()),
+            LossKind::Comment,
+            LossKind::SiblingOrder("b".into()),
+            LossKind::MixedContent("tail".into()),
+            LossKind::Namespace("urn:x".into()),
+            LossKind::TrimmedWhitespace,
+        ]
+    );
+    assert!(XmlToJson::default()
+        .xml_to_json_with_report("<a><b>simple</b></a>")
+        .unwrap()
+        .1
+        .is_lossless());
+    assert!(XmlToJson::default().strict().xml_to_json(xml).is_err());
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_conversion_warnings() {
+    let xml = "<a>AT&T &copy;<b><c><d/></c></b><e x=\"1\" x=\"2\">1</e><f>2</f></a>";
+    let (_, report) = XmlToJson::default()
+        .with_unknown_entities_as_text()
+        .with_duplicate_attributes(DuplicateAttributes::KeepFirst)
+        .with_max_convert_depth(2)
+        .with_max_output_bytes(50)
+        .xml_to_json_with_report(xml)
+        .unwrap();
+    let warnings: Vec<String> = report.warnings.iter().map(Warning::to_string).collect();
+    assert_eq!(
+        warnings,
+        [
+            "warning at '': unknown entity '&' kept as text",
+            "warning at '': unknown entity 'copy' kept as text",
+            "warning at '/a/b/c/d': depth limit reached, kept as raw XML",
+            "warning at '/a/e': duplicate attribute 'x'",
+            "warning at '/a': output truncated",
+        ]
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_binary_elements() {
+    use serde_json::json;
+
+    let xml = "<a><payload>aGVs\n bG8=</payload><signature>DEAD</signature></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_binary_elements(&["payload"], BinaryMode::Base64)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "payload": { "#text": "aGVsbG8=" }, "signature": { "#text": "DEAD" } })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_binary_elements(&["signature"], BinaryMode::Hex)
+            .with_binary_length_only()
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "payload": { "#text": "aGVs\n bG8=" }, "signature": { "#byte_length": 2 } })
+    );
+    assert!(XmlToJson::default()
+        .with_binary_elements(&["payload"], BinaryMode::Hex)
+        .xml_to_json(xml)
+        .is_err());
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_max_convert_depth() {
+    use serde_json::json;
+
+    let xml = "<a><b x=\"1\"><c><d>D</d></c></b><e>E</e></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_max_convert_depth(1)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b": { "@x": "1", "c": { "#raw": "<c><d>D</d></c>" } }, "e": { "#text": "E" } })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_root()
+            .with_max_convert_depth(0)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "a": { "b": { "#raw": "<b x=\"1\"><c><d>D</d></c></b>" }, "e": { "#raw": "<e>E</e>" } } })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_xml_to_json_bytes() {
+    use serde_json::json;
+
+    assert_eq!(
+        XmlToJson::default()
+            .xml_to_json_bytes(b"\xEF\xBB\xBF<a><b>simple</b></a>")
+            .unwrap(),
+        json!({ "b": { "#text": "simple" } })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .xml_to_json_bytes(b"\xFE\xFF\0<\0a\0>\0\xE4\0<\0/\0a\0>")
+            .unwrap(),
+        json!({ "#text": "\u{e4}" })
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_declaration() {
+    use serde_json::json;
+
+    let xml = "<?xml version=\"1.0\" encoding='UTF-8' standalone=\"yes\"?>\n<!DOCTYPE a [<!ENTITY x \"y\">]><a><b>simple</b></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_root()
+            .with_declaration()
+            .with_dtd()
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({
+            "#declaration": { "version": "1.0", "encoding": "UTF-8", "standalone": "yes", "doctype": "a" },
+            "a": { "b": { "#text": "simple" } }
+        })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_declaration()
+            .xml_to_json("<a/>")
+            .unwrap(),
+        json!({ "#declaration": {} })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_plural_names() {
+    use serde_json::json;
+
+    let xml = "<a><item>1</item><item>2</item><entry>3</entry><b>4</b></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_plural_names(&[("item", "items"), ("entry", "entries")])
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({
+            "items": [{ "#text": "1" }, { "#text": "2" }],
+            "entries": [{ "#text": "3" }],
+            "b": { "#text": "4" }
+        })
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_null_policy() {
+    use serde_json::json;
+
+    let xml = "<a xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"><b/><c xsi:nil=\"true\"/><d>D</d></a>";
+    let convert = |policy| {
+        XmlToJson::default()
+            .with_null_policy(policy)
+            .xml_to_json(xml)
+            .unwrap()
+    };
+    assert_eq!(
+        convert(NullPolicy::Skip),
+        json!({ "c": { "@nil": "true" }, "d": { "#text": "D" } })
+    );
+    assert_eq!(
+        convert(NullPolicy::XsiNil),
+        json!({ "c": null, "d": { "#text": "D" } })
+    );
+    assert_eq!(
+        convert(NullPolicy::Null),
+        json!({ "b": null, "c": null, "d": { "#text": "D" } })
+    );
+    assert_eq!(
+        convert(NullPolicy::Never),
+        json!({ "b": {}, "c": { "@nil": "true" }, "d": { "#text": "D" } })
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_node_to_json() {
+    use serde_json::json;
+
+    let doc = roxmltree::Document::parse("<a><b><c>C</c></b><d>D</d></a>").unwrap();
+    let node = doc
+        .descendants()
+        .find(|node| node.has_tag_name("b"))
+        .unwrap();
+    assert_eq!(
+        XmlToJson::default().node_to_json(&node).unwrap(),
+        Some(json!({ "c": { "#text": "C" } }))
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_root()
+            .node_to_json(&node)
+            .unwrap(),
+        Some(json!({ "b": { "c": { "#text": "C" } } }))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_html_entities() {
+    use serde_json::json;
+
+    let xml = "<a><b>Gr&uuml;&szlig;e&nbsp;&amp; more</b></a>";
+    assert!(XmlToJson::default().xml_to_json(xml).is_err());
+    assert_eq!(
+        XmlToJson::default()
+            .with_html_entities()
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b": { "#text": "Gr\u{fc}\u{df}e\u{a0}& more" } })
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_max_output_bytes() {
+    use serde_json::json;
+
+    let xml = "<a><b>1</b><b>2</b><c><d>3</d><d>4</d></c><e>5</e></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_max_output_bytes(40)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b": [{ "#text": "1" }, { "#text": "2" }], "c": { "d": { "#text": "3" }, "#truncated": true }, "#truncated": true })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_max_output_bytes(1000)
+            .xml_to_json(xml)
+            .unwrap(),
+        XmlToJson::default().xml_to_json(xml).unwrap()
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_namespace_mode() {
+    use serde_json::json;
+
+    let xml = "<a xmlns:xlink=\"http://www.w3.org/1999/xlink\"><xlink:b xlink:href=\"x\" href=\"y\"/></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_namespace_mode(NamespaceMode::Prefix)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "xlink:b": { "@xlink:href": "x", "@href": "y" } })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_namespace_mode(NamespaceMode::Expanded)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "{http://www.w3.org/1999/xlink}b": { "@{http://www.w3.org/1999/xlink}href": "x", "@href": "y" } })
+    );
+    let (value, report) = XmlToJson::default().xml_to_json_with_report(xml).unwrap();
+    assert_eq!(value, json!({ "b": { "@href": "y" } }));
+    assert!(report.losses.contains(&Loss {
+        path: "/a/b".into(),
+        kind: LossKind::Collision("@href".into(), "x".into())
+    }));
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_canonicalization() {
+    let parser = XmlToJson::default().with_canonicalization();
+    let a = "<a y=\"2\" x=\"1\">\n  <b>simple <![CDATA[text]]></b>\n</a>";
+    let b = "<a x=\"1\" y=\"2\"><b>simple text</b></a>";
+    assert_ne!(
+        XmlToJson::default().xml_to_json(a).unwrap(),
+        XmlToJson::default().xml_to_json(b).unwrap()
+    );
+    assert_eq!(
+        parser.xml_to_json(a).unwrap(),
+        parser.xml_to_json(b).unwrap()
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_type_overrides() {
+    use serde_json::json;
+
+    let xml = "<order id=\"007\" paid=\"true\"><total>12.5</total><code>42</code></order>";
+    let overrides = HashMap::from([
+        ("/order/total".to_string(), JsonType::Number),
+        ("/order/@paid".to_string(), JsonType::Bool),
+    ]);
+    assert_eq!(
+        XmlToJson::default()
+            .with_type_overrides(overrides)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "@id": "007", "@paid": true, "total": { "#text": 12.5 }, "code": { "#text": "42" } })
+    );
+    let overrides = HashMap::from([("/order/@id".to_string(), JsonType::Bool)]);
+    assert!(XmlToJson::default()
+        .with_type_overrides(overrides)
+        .xml_to_json(xml)
+        .is_err());
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_duplicate_attributes() {
+    use serde_json::json;
+
+    let xml = "<a><b x=\"1\" x=\"2\">B</b></a>";
+    assert!(XmlToJson::default().xml_to_json(xml).is_err());
+    let convert = |policy| {
+        XmlToJson::default()
+            .with_duplicate_attributes(policy)
+            .xml_to_json(xml)
+            .unwrap()
+    };
+    assert_eq!(
+        convert(DuplicateAttributes::KeepFirst),
+        json!({ "b": { "@x": "1", "#text": "B" } })
+    );
+    assert_eq!(
+        convert(DuplicateAttributes::KeepLast),
+        json!({ "b": { "@x": "2", "#text": "B" } })
+    );
+    assert_eq!(
+        convert(DuplicateAttributes::MakeArray),
+        json!({ "b": { "@x": ["1", "2"], "#text": "B" } })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_compact_attribute_elements() {
+    use serde_json::json;
+
+    let xml = r#"<svg w="10"><img src="a.png"/><rect x="1" y="2"/><text x="3">hi</text></svg>"#;
+    let parser = XmlToJson::default().with_compact_attribute_elements(&["img", "text"]);
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({
+            "@w": "10",
+            "img": { "src": "a.png" },
+            "rect": { "@x": "1", "@y": "2" },
+            "text": { "@x": "3", "#text": "hi" }
+        })
+    );
+    let parser = XmlToJson::default()
+        .with_compact_attribute_elements(&[])
+        .with_compact_attributes_key("attrs");
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap()["rect"],
+        json!({ "attrs": { "x": "1", "y": "2" } })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_case_insensitive_keys() {
+    use serde_json::json;
+
+    let xml = "<a><Item>1</Item><item>2</item><ITEM/><Other>3</Other></a>";
+    let parser = XmlToJson::default().with_case_insensitive_keys();
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({ "item": [{ "#text": "1" }, { "#text": "2" }], "other": { "#text": "3" } })
+    );
+    assert_eq!(
+        parser.with_original_names().xml_to_json(xml).unwrap()["item"],
+        json!([{ "#text": "1", "#originalName": "Item" }, { "#text": "2" }])
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_text_name_for() {
+    use serde_json::json;
+
+    let xml = "<a><script>run()</script><style>b {}</style><style>i {}</style><p>text</p></a>";
+    let parser = XmlToJson::default()
+        .with_text_name_for(&[("script", "#script_text"), ("style", "#css")])
+        .with_flatten_text_arrays();
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({
+            "script": { "#script_text": "run()" },
+            "style": ["b {}", "i {}"],
+            "p": { "#text": "text" }
+        })
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_source_spans() {
+    use serde_json::json;
+
+    let xml = "<a>\n  <b>x</b>\n  <c/>\n</a>";
+    let value = XmlToJson::default()
+        .with_source_spans()
+        .xml_to_json(xml)
+        .unwrap();
+    assert_eq!(
+        value["b"],
+        json!({ "#text": "x", "#span": { "start": 6, "end": 14, "line": 2, "col": 3 } })
+    );
+    assert_eq!(value["#span"]["end"], json!(xml.len()));
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_input_sanitizing() {
+    use serde_json::json;
+
+    let xml = "\u{feff}\r\n  <?xml version=\"1.0\"?><a>x</a>";
+    assert!(XmlToJson::default().xml_to_json(xml).is_err());
+    let parser = XmlToJson::default().with_input_sanitizing(false);
+    assert_eq!(parser.xml_to_json(xml).unwrap(), json!({ "#text": "x" }));
+    assert!(parser.xml_to_json("HTTP/1.1 200 OK<a>x</a>").is_err());
+    let parser = XmlToJson::default().with_input_sanitizing(true);
+    assert_eq!(
+        parser.xml_to_json("HTTP/1.1 200 OK<a>x</a>").unwrap(),
+        json!({ "#text": "x" })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_namespace_declarations() {
+    use serde_json::json;
+
+    let xml = r#"<a xmlns="urn:a" xmlns:x="urn:x"><b x:id="1"/><c xmlns:y="urn:y">t</c></a>"#;
+    let parser = XmlToJson::default().with_namespace_declarations(NamespaceDeclarations::Keep);
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({
+            "@xmlns": "urn:a",
+            "@xmlns:x": "urn:x",
+            "b": { "@id": "1" },
+            "c": { "@xmlns:y": "urn:y", "#text": "t" }
+        })
+    );
+    let parser = XmlToJson::default().with_namespace_declarations(NamespaceDeclarations::Collect);
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({
+            "#namespaces": { "": "urn:a", "x": "urn:x" },
+            "b": { "@id": "1" },
+            "c": { "#namespaces": { "y": "urn:y" }, "#text": "t" }
+        })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_sibling_index() {
+    use serde_json::json;
+
+    let xml = "<a><x>1</x><y>2</y><x>3</x></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_sibling_index()
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({
+            "x": [{ "#text": "1", "#index": 0 }, { "#text": "3", "#index": 2 }],
+            "y": { "#text": "2", "#index": 1 }
+        })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_input_validation() {
+    let parser = XmlToJson::default()
+        .with_max_attributes_per_element(2)
+        .with_max_name_length(8);
+    assert!(parser.xml_to_json("<a x=\"1\" y=\"2\"><b/></a>").is_ok());
+    let err = parser.xml_to_json("<a><b x=\"1\" y=\"2\" z=\"3\"/></a>");
+    assert_eq!(
+        err.unwrap_err().to_string(),
+        "too many attributes at '/a/b'"
+    );
+    assert!(parser.xml_to_json("<a><very_long_name/></a>").is_err());
+    assert!(parser.xml_to_json("<a very_long_name=\"1\"/>").is_err());
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_strip_namespaces() {
+    use serde_json::json;
+
+    let xml = r#"<Envelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:m="urn:m">
+        <m:order xsi:type="m:Order" m:id="1"><m:item>x</m:item><xsi:note>n</xsi:note></m:order>
+    </Envelope>"#;
+    let parser = XmlToJson::default()
+        .with_namespace_mode(NamespaceMode::Prefix)
+        .with_strip_namespaces(&["http://www.w3.org/2001/XMLSchema-instance"]);
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap()["m:order"],
+        json!({ "@m:id": "1", "m:item": { "#text": "x" } })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_preserve_attribute_whitespace() {
+    use serde_json::json;
+
+    let xml = r#"<a code="  42" pass=" x "> text </a>"#;
+    assert_eq!(
+        XmlToJson::default()
+            .with_preserve_attribute_whitespace()
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "@code": "  42", "@pass": " x ", "#text": "text" })
+    );
+    assert_eq!(
+        XmlToJson::default().xml_to_json(xml).unwrap()["@code"],
+        json!("42")
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_attribute_normalization() {
+    use serde_json::json;
+
+    let xml = "<a note=\"line 1\n\tline 2&#10;&amp;\"/>";
+    let raw = XmlToJson::default().with_attribute_normalization(AttributeNormalization::Raw);
+    assert_eq!(
+        XmlToJson::default().xml_to_json(xml).unwrap(),
+        json!({ "@note": "line 1  line 2\n&" })
+    );
+    let value = raw.xml_to_json(xml).unwrap();
+    assert_eq!(value, json!({ "@note": "line 1\n\tline 2\n&" }));
+    let written = raw.json_to_xml_pretty(&value, "a", 0).unwrap();
+    assert_eq!(raw.xml_to_json(&written).unwrap(), value);
+    #[cfg(feature = "quick-xml")]
+    for parser in [XmlToJson::default(), raw] {
+        assert_eq!(
+            parser.xml_to_json_with_quick_xml(xml).unwrap(),
+            parser.xml_to_json(xml).unwrap()
+        );
+    }
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_preserve_attribute_order() {
+    let xml = r#"<a><b z="1" href="x" class="y"/></a>"#;
+    let parser = XmlToJson::default()
+        .with_root()
+        .with_preserve_attribute_order();
+    let value = parser.xml_to_json(xml).unwrap();
+    assert_eq!(
+        value["a"]["b"]["#attr_order"],
+        serde_json::json!(["z", "href", "class"])
+    );
+    assert_eq!(
+        parser.json_to_xml_pretty(&value, "", 0).unwrap(),
+        "<a>\n<b z=\"1\" href=\"x\" class=\"y\"/>\n</a>\n"
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_array_mode() {
+    use serde_json::json;
+
+    let xml = "<a><b>1</b><c><d>2</d></c></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_array_mode(ArrayMode::AlwaysWrapChildren)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b": [{ "#text": "1" }], "c": [{ "d": [{ "#text": "2" }] }] })
+    );
+    let strict = XmlToJson::default().with_array_mode(ArrayMode::ErrorOnUnexpectedRepeat);
+    let xml = "<a><b>1</b><b>2</b></a>";
+    assert_eq!(
+        strict.xml_to_json(xml).unwrap_err().to_string(),
+        "unexpected repeated element 'b' at '/a'"
+    );
+    let strict = strict.with_array_paths(&["/a/b"]);
+    assert_eq!(
+        strict.xml_to_json(xml).unwrap(),
+        json!({ "b": [{ "#text": "1" }, { "#text": "2" }] })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_inline_simple_children() {
+    use serde_json::json;
+
+    let xml = r#"<person><name>Ann</name><age>7</age><tag>a</tag><tag x="1">b</tag></person>"#;
+    assert_eq!(
+        XmlToJson::default()
+            .with_inline_simple_children(InlineConflict::Mixed)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "name": "Ann", "age": "7", "tag": ["a", { "@x": "1", "#text": "b" }] })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_inline_simple_children(InlineConflict::KeepObjects)
+            .xml_to_json(xml)
+            .unwrap()["tag"],
+        json!([{ "#text": "a" }, { "@x": "1", "#text": "b" }])
+    );
+}
+
+#[cfg(all(feature = "std", feature = "roxmltree"))]
+#[test]
+fn test_xml_to_json_bytes_with_report() {
+    use serde_json::json;
+
+    let (value, report) = XmlToJson::default()
+        .with_utf8_policy(Utf8Policy::Replace)
+        .xml_to_json_bytes_with_report(b"<a><b>x\xFFy</b></a>")
+        .unwrap();
+    assert_eq!(value, json!({ "b": { "#text": "x\u{fffd}y" } }));
+    assert_eq!(report.warnings[0].kind, WarningKind::InvalidUtf8(7));
+    assert!(XmlToJson::default()
+        .xml_to_json_bytes(b"<a>\xFF</a>")
+        .is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_arbitrary_input_never_panics() {
+    use std::collections::HashMap;
+
+    // fragments that reach the special cases of the parser and the preprocessing steps
+    const FRAGMENTS: [&str; 40] = [
+        "<a>",
+        "</a>",
+        "<b x=\"1\">",
+        "</b>",
+        "<b/>",
+        "<c x='1' x='2'/>",
+        "text",
+        " ",
+        "\n",
+        "&amp;",
+        "&auml;",
+        "&e;",
+        "&#xFFFF;",
+        "&",
+        ";",
+        "<!--",
+        "-->",
+        "<![CDATA[",
+        "]]>",
+        "<?pi ",
+        "?>",
+        "<!DOCTYPE a [",
+        "<!ENTITY e \"<b/>\">",
+        "<!ENTITY f SYSTEM \"f\">",
+        "]>",
+        "<",
+        ">",
+        "\"",
+        "'",
+        "=",
+        "xmlns:n=\"urn:n\"",
+        "<n:d n:y=\"2\">",
+        "</n:d>",
+        "\u{e4}",
+        "\u{feff}",
+        "<xi:include xmlns:xi=\"http://www.w3.org/2001/XInclude\" href=\"i\"/>",
+        "<item>",
+        "</item>",
+        "xsi:nil=\"true\"",
+        "<?xml version=\"1.0\"?>",
+    ];
+    let mut entities = HashMap::new();
+    entities.insert("e".to_string(), "<&>".to_string());
+    let mut includes = HashMap::new();
+    includes.insert("i".to_string(), "<i>&e;</i>".to_string());
+    let parsers = [
+        XmlToJson::default(),
+        XmlToJson::default()
+            .with_root()
+            .with_text_name("")
+            .with_attribute_prefix("")
+            .with_plural_names(&[("b", "b"), ("item", "")])
+            .with_key_rename(KeyRename::prefix("", "x"))
+            .with_key_rename(KeyRename::key("b", "@x"))
+            .with_flatten_text_arrays()
+            .with_null_policy(NullPolicy::Never),
+        XmlToJson::default()
+            .with_dtd()
+            .with_html_entities()
+            .with_entities(entities)
+            .with_unknown_entities_as_text()
+            .with_duplicate_attributes(DuplicateAttributes::MakeArray)
+            .with_input_sanitizing(true)
+            .with_include_resolver(includes)
+            .with_canonicalization()
+            .with_declaration(),
+        XmlToJson::default()
+            .with_dtd()
+            .with_entity_resolver(|_: &str, _: Option<&str>| Ok("<!ENTITY g '<b>'>".into()))
+            .with_preserve_attribute_whitespace()
+            .with_duplicate_attributes(DuplicateAttributes::KeepLast),
+        XmlToJson::default()
+            .with_max_convert_depth(1)
+            .with_max_output_bytes(10)
+            .with_case_insensitive_keys()
+            .with_original_names()
+            .with_compact_attribute_elements(&[])
+            .with_sibling_index()
+            .with_source_spans()
+            .with_flatten("")
+            .with_inline_simple_children(InlineConflict::Mixed)
+            .with_null_policy(NullPolicy::XsiNil),
+        XmlToJson::default()
+            .with_root()
+            .with_attribute_prefix("")
+            .with_plural_names(&[("item", "items"), ("c", "b")])
+            .with_namespace_declarations(NamespaceDeclarations::Collect)
+            .with_array_mode(ArrayMode::AlwaysWrapChildren)
+            .with_null_policy(NullPolicy::Null),
+    ];
+    // xorshift, so failures are reproducible without a random number crate
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = |max: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as usize % max
+    };
+    // mostly short documents, every tenth one is long enough for deep nesting and large
+    // attribute lists. `fuzz/` has a coverage guided harness for longer runs.
+    for round in 0..1000 {
+        let len = next(if round % 10 == 0 { 400 } else { 40 });
+        let mut xml: String = (0..len).map(|_| FRAGMENTS[next(FRAGMENTS.len())]).collect();
+        if round % 2 == 0 {
+            xml = ["<a>", &xml, "</a>"].concat();
+        }
+        for parser in &parsers {
+            #[cfg(feature = "roxmltree")]
+            if parser.streams_to_sink() {
+                assert_eq!(
+                    parser.xml_to_sink(&xml, ValueSink::default()).ok(),
+                    parser.xml_to_json(&xml).ok(),
+                    "{xml}"
+                );
+            }
+            if let Ok(value) = parser.xml_to_json(&xml) {
+                let _ = parser.json_to_xml_pretty(&value, "", 2);
+                let _ = parser.json_to_xml_pretty(&value, "r", 0);
+                let _ = parser.query(&value, "$..b[*].*");
+                let _ = parser.query(&value, &xml);
+                let _ = parser.prepare_for_quick_xml(value.clone());
+                let mut merged = value.clone();
+                MergeRules::default().merge(&mut merged, value);
+            }
+            let _ = parser.xml_to_json_bytes(xml.as_bytes());
+            #[cfg(feature = "quick-xml")]
+            let _ = parser.xml_to_json_with_quick_xml(&xml);
+            #[cfg(feature = "roxmltree")]
+            {
+                let _ = parser.xml_to_json_partial(&xml);
+                let _ = parser.xml_to_events(&xml, |_| {});
+                let _ = parser.json_pointer(&xml, "/a/b[2]/@x");
+                let _ = parser.xml_path(&xml, "/b/1/@x");
+            }
+            let _ = parser.to_records(&xml, "$.b[*]");
+        }
+        let mut incremental = XmlToJson::default().into_incremental().with_records("b");
+        for chunk in xml.as_bytes().chunks(3) {
+            let _ = incremental.push(chunk);
+        }
+        let _ = incremental.finish();
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_deeply_nested_input() {
+    let depth = 20_000;
+    let nested = ["<a>".repeat(depth), "</a>".repeat(depth)].concat();
+    let unclosed = "<a x='>'>".repeat(depth);
+    let entity = format!(
+        "<!DOCTYPE a [<!ENTITY e '{}{}'>]><a>&e;</a>",
+        "<b>".repeat(30),
+        "</b>".repeat(30)
+    );
+    let parser = XmlToJson::default().with_dtd();
+    for xml in [&nested, &unclosed, &entity] {
+        let err = parser.xml_to_json(xml).unwrap_err();
+        assert!(err.to_string().contains("nesting depth"));
+        #[cfg(feature = "quick-xml")]
+        assert!(parser.xml_to_json_with_quick_xml(xml).is_err());
+        #[cfg(feature = "roxmltree")]
+        {
+            assert!(parser.xml_to_json_partial(xml).1.is_some());
+            assert!(parser.xml_to_events(xml, |_| {}).is_err());
+            assert!(parser.json_pointer(xml, "/a").is_err());
+            assert!(parser.collect_elements(xml, "a").is_err());
+            assert!(parser.parse_document(xml).is_err());
+            assert!(XmlNode::parse(xml).is_err());
+            assert!(TypeInference::learn(&[xml]).is_err());
+        }
+    }
+    let allowed = ["<a>".repeat(256), "</a>".repeat(256)].concat();
+    assert!(parser.xml_to_json(&allowed).is_ok());
+    assert!(XmlToJson::default()
+        .with_max_nesting_depth(10)
+        .xml_to_json(&allowed)
+        .is_err());
 }
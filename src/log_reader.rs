@@ -0,0 +1,156 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{Incremental, XmlToJson};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::{BufRead, Seek, SeekFrom};
+
+type RecordResult = Result<Value, Box<dyn std::error::Error>>;
+
+/// the maximum number of bytes passed to the incremental parser at once
+const MAX_CHUNK_LEN: usize = 64 * 1024;
+
+/// Reads concatenated XML records, like Windows Event Log exports or JMeter results, and yields
+/// each record element as separate JSON value. Records don't need a common root element.
+/// A record that can't be converted yields an error, reading continues with the next record.
+/// Use `checkpoint` and `resume_from` to continue an interrupted conversion of a large file.
+pub struct XmlLogReader<R> {
+    reader: R,
+    incremental: Option<Incremental>,
+    /// converted records or errors and the input offset behind each of them
+    pending: VecDeque<(RecordResult, u64)>,
+    /// input offset behind the last yielded record
+    checkpoint: u64,
+    /// the last chunk was not scanned completely after a failed record
+    rescan: bool,
+}
+
+impl<R: BufRead> XmlLogReader<R> {
+    /// convert every element named `record_name` of `reader` with `parser`
+    pub fn new(reader: R, parser: XmlToJson, record_name: &str) -> Self {
+        XmlLogReader {
+            reader,
+            incremental: Some(parser.into_incremental().with_records(record_name)),
+            pending: VecDeque::new(),
+            checkpoint: 0,
+            rescan: false,
+        }
+    }
+
+    /// the byte offset behind the last record that was yielded, also if it was an error.
+    /// Store it after processing a record to resume from there with `resume_from`.
+    pub fn checkpoint(&self) -> u64 {
+        self.checkpoint
+    }
+
+    fn read_chunk(&mut self) -> Option<Result<(), Box<dyn std::error::Error>>> {
+        let incremental = self.incremental.as_mut()?;
+        if std::mem::take(&mut self.rescan) {
+            return Some(incremental.push(&[]));
+        }
+        let chunk = match self.reader.fill_buf() {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                self.incremental = None;
+                return Some(Err(err.into()));
+            }
+        };
+        if chunk.is_empty() {
+            // report an unfinished record at the end of the input
+            return self.incremental.take()?.finish().err().map(Err);
+        }
+        // a reader over a slice returns all of it at once, like a `MappedFile`
+        let len = chunk.len().min(MAX_CHUNK_LEN);
+        let result = incremental.push(&chunk[..len]);
+        self.reader.consume(len);
+        Some(result)
+    }
+}
+
+impl<R: BufRead + Seek> XmlLogReader<R> {
+    /// like `new`, but skip the input in front of `offset`, which is a previous `checkpoint`
+    pub fn resume_from(
+        mut reader: R,
+        parser: XmlToJson,
+        record_name: &str,
+        offset: u64,
+    ) -> std::io::Result<Self> {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut log_reader = XmlLogReader::new(reader, parser, record_name);
+        log_reader.checkpoint = offset;
+        log_reader.incremental = log_reader
+            .incremental
+            .map(|incremental| incremental.resume_from(offset));
+        Ok(log_reader)
+    }
+}
+
+impl<R: BufRead> Iterator for XmlLogReader<R> {
+    type Item = RecordResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((record, offset)) = self.pending.pop_front() {
+                self.checkpoint = offset;
+                return Some(record);
+            }
+            let result = self.read_chunk()?;
+            if let Some(incremental) = self.incremental.as_mut() {
+                let records = incremental.records_with_offsets();
+                self.pending.extend(
+                    records
+                        .into_iter()
+                        .map(|(record, offset)| (Ok(record), offset)),
+                );
+            }
+            if let Err(err) = result {
+                let checkpoint = self
+                    .incremental
+                    .as_ref()
+                    .map_or(self.checkpoint, Incremental::checkpoint);
+                self.pending.push_back((Err(err), checkpoint));
+                self.rescan = true;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_xml_log_reader() {
+    use serde_json::json;
+
+    let log = "<Event><Id>1</Id></Event>\n<Event><Id>2</Id><Bad></Event>\n\
+        <Event><Id>3</Id></Event>\n<Event><Id>4</Id>";
+    let reader = std::io::BufReader::with_capacity(7, log.as_bytes());
+    let records: Vec<_> = XmlLogReader::new(reader, XmlToJson::default(), "Event").collect();
+    assert_eq!(records.len(), 4);
+    assert_eq!(
+        records[0].as_ref().unwrap(),
+        &json!({ "Id": { "#text": "1" } })
+    );
+    assert!(records[1].is_err());
+    assert_eq!(
+        records[2].as_ref().unwrap(),
+        &json!({ "Id": { "#text": "3" } })
+    );
+    assert!(records[3].is_err());
+}
+
+#[test]
+fn test_xml_log_reader_resume() {
+    use std::io::Cursor;
+
+    let log =
+        "<log><Event><Id>1</Id></Event><Event><Id>2</Id></Event><Event><Id>3</Id></Event></log>";
+    let mut reader = XmlLogReader::new(Cursor::new(log), XmlToJson::default(), "Event");
+    reader.next().unwrap().unwrap();
+    assert_eq!(reader.checkpoint(), 30);
+    let all: Vec<_> = reader.map(Result::unwrap).collect();
+    let resumed =
+        XmlLogReader::resume_from(Cursor::new(log), XmlToJson::default(), "Event", 30).unwrap();
+    assert_eq!(resumed.map(Result::unwrap).collect::<Vec<_>>(), all);
+    assert_eq!(all.len(), 2);
+}
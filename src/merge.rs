@@ -0,0 +1,126 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::Value;
+
+/// How arrays are merged, see `MergeRules`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArrayMerge {
+    /// append the values of later documents, a single value is treated like an array with one element
+    Concat,
+    /// keep the value of the last document
+    LastWins,
+}
+
+/// How strings and other scalars are merged, see `MergeRules`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScalarMerge {
+    /// keep the value of the last document
+    LastWins,
+    /// keep the value of the first document
+    FirstWins,
+    /// collect different values into an array
+    Collect,
+}
+
+/// Rules for `XmlToJson::merge_xml_documents`, objects are always merged key by key
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MergeRules {
+    pub arrays: ArrayMerge,
+    pub scalars: ScalarMerge,
+}
+
+impl Default for MergeRules {
+    fn default() -> Self {
+        MergeRules {
+            arrays: ArrayMerge::Concat,
+            scalars: ScalarMerge::LastWins,
+        }
+    }
+}
+
+impl MergeRules {
+    /// deep-merge `other` into `base`
+    pub fn merge(&self, base: &mut Value, other: Value) {
+        match (base, other) {
+            (Value::Object(base_map), Value::Object(other_map)) => {
+                for (key, val) in other_map {
+                    match base_map.get_mut(&key) {
+                        Some(base_val) => self.merge(base_val, val),
+                        None => {
+                            base_map.insert(key, val);
+                        }
+                    }
+                }
+            }
+            (base @ Value::Array(_), other) | (base, other @ Value::Array(_)) => {
+                match self.arrays {
+                    ArrayMerge::Concat => {
+                        let mut values = Self::into_vec(base.take());
+                        values.extend(Self::into_vec(other));
+                        *base = Value::Array(values);
+                    }
+                    ArrayMerge::LastWins => *base = other,
+                }
+            }
+            (base, other) => match self.scalars {
+                ScalarMerge::LastWins => *base = other,
+                ScalarMerge::FirstWins => {}
+                ScalarMerge::Collect if *base == other => {}
+                ScalarMerge::Collect => *base = Value::Array(vec![base.take(), other]),
+            },
+        }
+    }
+
+    fn into_vec(value: Value) -> Vec<Value> {
+        match value {
+            Value::Array(array) => array,
+            Value::Null => Vec::new(),
+            other => vec![other],
+        }
+    }
+}
+
+impl XmlToJson {
+    /// Convert several XML documents, for example paginated API responses, and
+    /// deep-merge them into a single JSON value, see `with_merge_rules`
+    pub fn merge_xml_documents(&self, docs: &[&str]) -> Result<Value, Box<dyn std::error::Error>> {
+        let mut result = Value::Null;
+        for doc in docs {
+            let value = self.xml_to_json(doc)?;
+            if result.is_null() {
+                result = value;
+            } else {
+                self.merge_rules.merge(&mut result, value);
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[test]
+fn test_merge_xml_documents() {
+    use serde_json::json;
+
+    let docs = [
+        "<a page=\"1\"><item>1</item><item>2</item></a>",
+        "<a page=\"2\"><item>3</item></a>",
+    ];
+    assert_eq!(
+        XmlToJson::default().merge_xml_documents(&docs).unwrap(),
+        json!({ "@page": "2", "item": [{ "#text": "1" }, { "#text": "2" }, { "#text": "3" }] })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_merge_rules(MergeRules {
+                arrays: ArrayMerge::LastWins,
+                scalars: ScalarMerge::Collect,
+            })
+            .merge_xml_documents(&docs)
+            .unwrap(),
+        json!({ "@page": ["1", "2"], "item": { "#text": "3" } })
+    );
+}
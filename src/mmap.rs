@@ -0,0 +1,79 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::Value;
+use std::ops::Deref;
+use std::path::Path;
+
+/// A read-only file mapped into memory with `memmap2`. Dereferences to the file content,
+/// so it can also be passed to `XmlLogReader` as `&file[..]` to convert large files record
+/// by record.
+pub struct MappedFile(memmap2::Mmap);
+
+impl MappedFile {
+    /// map the file at `path` into memory
+    ///
+    /// # Safety
+    ///
+    /// The file must not be truncated or modified, also not by other processes, while the
+    /// `MappedFile` exists. Otherwise reading the content is undefined behaviour or
+    /// terminates the process with `SIGBUS`. Read the file with `std::fs::read` if this
+    /// can't be guaranteed.
+    pub unsafe fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: guaranteed by the caller
+        Ok(MappedFile(unsafe { memmap2::Mmap::map(&file)? }))
+    }
+}
+
+impl Deref for MappedFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl XmlToJson {
+    /// Parse an XML file without reading it into a String first. The file is mapped into
+    /// memory, UTF-8 input is parsed directly from the mapping.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified during the conversion, see `MappedFile::open`.
+    pub unsafe fn xml_to_json_mmap(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Value, Box<dyn std::error::Error>> {
+        // SAFETY: guaranteed by the caller
+        let file = unsafe { MappedFile::open(path)? };
+        self.xml_to_json_bytes(&file)
+    }
+}
+
+#[test]
+fn test_xml_to_json_mmap() {
+    use serde_json::json;
+
+    let name = format!("xml_to_json_mmap_test_{}.xml", std::process::id());
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, "<a><b>x</b><b>y</b></a>").unwrap();
+    // SAFETY: the file is only written by this test
+    let value = unsafe { XmlToJson::default().xml_to_json_mmap(&path) };
+    assert_eq!(
+        value.unwrap(),
+        json!({ "b": [{ "#text": "x" }, { "#text": "y" }] })
+    );
+    let file = unsafe { MappedFile::open(&path).unwrap() };
+    let records = crate::XmlLogReader::new(&file[..], XmlToJson::default(), "b");
+    assert_eq!(records.count(), 2);
+    drop(file);
+    std::fs::write(&path, "").unwrap();
+    let file = unsafe { MappedFile::open(&path).unwrap() };
+    assert!(file.is_empty());
+    drop(file);
+    std::fs::remove_file(&path).unwrap();
+}
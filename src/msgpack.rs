@@ -0,0 +1,142 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{JsonSink, XmlToJson};
+use serde_json::Number;
+
+/// Encodes the converted document as MessagePack, see `XmlToJson::xml_to_msgpack`
+#[derive(Default)]
+pub struct MsgPackSink {
+    buf: Vec<u8>,
+}
+
+impl MsgPackSink {
+    /// write a marker for `len` items, using the fix format with `fix_marker` if possible
+    fn write_len(&mut self, len: usize, fix_marker: u8, fix_max: usize, markers: [u8; 3]) {
+        if len <= fix_max {
+            self.buf.push(fix_marker | len as u8);
+        } else if markers[0] != 0 && len <= u8::MAX as usize {
+            self.buf.extend([markers[0], len as u8]);
+        } else if len <= u16::MAX as usize {
+            self.buf.push(markers[1]);
+            self.buf.extend((len as u16).to_be_bytes());
+        } else {
+            self.buf.push(markers[2]);
+            self.buf.extend((len as u32).to_be_bytes());
+        }
+    }
+}
+
+impl JsonSink for MsgPackSink {
+    type Output = Vec<u8>;
+    fn null(&mut self) {
+        self.buf.push(0xc0);
+    }
+    fn bool(&mut self, val: bool) {
+        self.buf.push(if val { 0xc3 } else { 0xc2 });
+    }
+    fn number(&mut self, val: &Number) {
+        if let Some(num) = val.as_u64() {
+            match num {
+                0..=0x7f => self.buf.push(num as u8),
+                0x80..=0xff => self.buf.extend([0xcc, num as u8]),
+                0x100..=0xffff => {
+                    self.buf.push(0xcd);
+                    self.buf.extend((num as u16).to_be_bytes());
+                }
+                0x1_0000..=0xffff_ffff => {
+                    self.buf.push(0xce);
+                    self.buf.extend((num as u32).to_be_bytes());
+                }
+                _ => {
+                    self.buf.push(0xcf);
+                    self.buf.extend(num.to_be_bytes());
+                }
+            }
+        } else if let Some(num) = val.as_i64() {
+            match num {
+                -32..=-1 => self.buf.push(num as u8),
+                -0x80..=-33 => self.buf.extend([0xd0, num as u8]),
+                -0x8000..=-0x81 => {
+                    self.buf.push(0xd1);
+                    self.buf.extend((num as i16).to_be_bytes());
+                }
+                -0x8000_0000..=-0x8001 => {
+                    self.buf.push(0xd2);
+                    self.buf.extend((num as i32).to_be_bytes());
+                }
+                _ => {
+                    self.buf.push(0xd3);
+                    self.buf.extend(num.to_be_bytes());
+                }
+            }
+        } else {
+            self.buf.push(0xcb);
+            self.buf
+                .extend(val.as_f64().unwrap_or_default().to_be_bytes());
+        }
+    }
+    fn string(&mut self, val: &str) {
+        self.write_len(val.len(), 0xa0, 31, [0xd9, 0xda, 0xdb]);
+        self.buf.extend(val.as_bytes());
+    }
+    fn begin_object(&mut self, len: usize) {
+        self.write_len(len, 0x80, 15, [0, 0xde, 0xdf]);
+    }
+    fn key(&mut self, key: &str) {
+        self.string(key);
+    }
+    fn end_object(&mut self) {}
+    fn begin_array(&mut self, len: usize) {
+        self.write_len(len, 0x90, 15, [0, 0xdc, 0xdd]);
+    }
+    fn end_array(&mut self) {}
+    fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+impl XmlToJson {
+    /// Parse XML string and return it encoded as MessagePack. The value is encoded while
+    /// traversing the XML, without a `Value` in between, unless an option of `xml_to_sink`
+    /// needs it.
+    pub fn xml_to_msgpack(&self, xml: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        self.xml_to_sink(xml, MsgPackSink::default())
+    }
+}
+
+#[test]
+fn test_xml_to_msgpack() {
+    let xml = "<a><b>x</b><b>y</b></a>";
+    let parser = XmlToJson::default().with_flatten_text_arrays();
+    assert_eq!(
+        parser.xml_to_msgpack(xml).unwrap(),
+        [0x81, 0xa1, b'b', 0x92, 0xa1, b'x', 0xa1, b'y']
+    );
+    let parser = XmlToJson::default();
+    #[cfg(feature = "roxmltree")]
+    assert!(parser.streams_to_sink());
+    assert_eq!(
+        parser.xml_to_msgpack("<a x=\"1\"><b>y</b></a>").unwrap(),
+        [
+            &[0x82, 0xa2, b'@', b'x', 0xa1, b'1'][..],
+            &[0xa1, b'b', 0x81, 0xa5, b'#', b't', b'e', b'x', b't', 0xa1, b'y'],
+        ]
+        .concat()
+    );
+    let mut sink = MsgPackSink::default();
+    for num in [1, -1, 200, -200, 70000] {
+        sink.number(&num.into());
+    }
+    sink.number(&Number::from_f64(0.5).unwrap());
+    assert_eq!(
+        sink.finish(),
+        [
+            vec![0x01, 0xff, 0xcc, 200, 0xd1, 0xff, 0x38, 0xce, 0, 1, 0x11, 0x70, 0xcb],
+            0.5f64.to_be_bytes().to_vec()
+        ]
+        .concat()
+    );
+}
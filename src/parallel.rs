@@ -0,0 +1,75 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::incremental::record_ranges;
+use crate::XmlToJson;
+use serde_json::Value;
+
+impl XmlToJson {
+    /// Convert each element named `record_name` of a large document as a separate record on
+    /// `threads` threads, or one per CPU core if `threads` is 0. Returns the records in
+    /// document order, nested records with the same name are part of the outer one.
+    /// Records are converted as standalone documents, so namespaces need to be declared
+    /// on the records and content outside of them is ignored.
+    pub fn xml_to_json_parallel(
+        &self,
+        xml: &str,
+        record_name: &str,
+        threads: usize,
+    ) -> Result<Vec<Value>, Box<dyn std::error::Error>> {
+        let ranges = record_ranges(xml.as_bytes(), record_name.as_bytes());
+        let threads = match threads {
+            0 => std::thread::available_parallelism().map_or(1, usize::from),
+            threads => threads,
+        };
+        let chunk_len = ranges.len().div_ceil(threads).max(1);
+        let results: Vec<Result<Vec<Value>, String>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = ranges
+                .chunks(chunk_len)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|range| {
+                                self.xml_to_json(&xml[range.clone()])
+                                    .map_err(|err| format!("record at byte {}: {err}", range.start))
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|_| Err("conversion panicked".into()))
+                })
+                .collect()
+        });
+        let mut records = Vec::with_capacity(ranges.len());
+        for result in results {
+            records.extend(result?);
+        }
+        Ok(records)
+    }
+}
+
+#[test]
+fn test_xml_to_json_parallel() {
+    let entries: String = (0..100)
+        .map(|id| format!("<entry id=\"{id}\"><entry>{id}</entry><!-- </entry> --></entry>"))
+        .collect();
+    let xml = format!("<feed><title>t</title>{entries}<entry/></feed>");
+    let parser = XmlToJson::default();
+    let records = parser.xml_to_json_parallel(&xml, "entry", 4).unwrap();
+    let mut sequential = XmlToJson::incremental().with_records("entry");
+    sequential.push(xml.as_bytes()).unwrap();
+    assert_eq!(records.len(), 101);
+    assert_eq!(records, sequential.records());
+    assert!(parser
+        .xml_to_json_parallel("<feed><entry><a></entry></feed>", "entry", 0)
+        .is_err());
+}
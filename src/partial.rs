@@ -0,0 +1,174 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{Context, XmlToJson};
+use serde_json::Value;
+
+/// returns the byte offset of a 1-based row and column position
+fn byte_offset(xml: &str, pos: roxmltree::TextPos) -> usize {
+    let line_start: usize = xml
+        .split_inclusive('\n')
+        .take(pos.row.saturating_sub(1) as usize)
+        .map(str::len)
+        .sum();
+    xml[line_start..]
+        .char_indices()
+        .nth(pos.col.saturating_sub(1) as usize)
+        .map_or(xml.len(), |(offset, _)| line_start + offset)
+}
+
+/// Cut `xml` after the last complete markup before `end` and close all open elements
+fn repair_truncated(xml: &str, end: usize) -> String {
+    let input = &xml[..end];
+    let mut open: Vec<&str> = Vec::new();
+    let mut safe = 0;
+    let mut pos = 0;
+    while let Some(start) = input[pos..].find('<').map(|offset| pos + offset) {
+        // a text may only be cut in front of an unfinished entity
+        let text = &input[pos..start];
+        safe = pos
+            + text
+                .rfind('&')
+                .filter(|amp| !text[*amp..].contains(';'))
+                .unwrap_or(text.len());
+        if safe < start {
+            break;
+        }
+        let rest = &input[start..];
+        let close = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else if rest.starts_with("<![CDATA[") {
+            rest.find("]]>").map(|end| end + 3)
+        } else if rest.starts_with("<?") {
+            rest.find("?>").map(|end| end + 2)
+        } else if rest.starts_with("<!") {
+            match rest.find(['[', '>']) {
+                Some(bracket) if rest[bracket..].starts_with('[') => {
+                    rest.find("]>").map(|end| end + 2)
+                }
+                other => other.map(|end| end + 1),
+            }
+        } else {
+            tag_end(rest)
+        };
+        let Some(len) = close else { break };
+        let tag = &rest[..len];
+        if let Some(name) = tag.strip_prefix("</") {
+            if open.last() != Some(&name.trim_end_matches('>').trim()) {
+                break;
+            }
+            open.pop();
+        } else if !tag.starts_with("<!") && !tag.starts_with("<?") && !tag.ends_with("/>") {
+            let name_end = tag[1..]
+                .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .map_or(tag.len(), |offset| offset + 1);
+            open.push(&tag[1..name_end]);
+        }
+        pos = start + len;
+        safe = pos;
+    }
+    if !input[pos..].contains('<') {
+        let text = &input[pos..];
+        safe = pos
+            + text
+                .rfind('&')
+                .filter(|amp| !text[*amp..].contains(';'))
+                .unwrap_or(text.len());
+    }
+    let mut repaired = input[..safe].to_string();
+    for name in open.iter().rev() {
+        repaired.push_str("</");
+        repaired.push_str(name);
+        repaired.push('>');
+    }
+    repaired
+}
+
+/// returns the length of a start or end tag, ignoring `>` inside of quoted attribute values
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (pos, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return Some(pos + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+impl XmlToJson {
+    /// Parse a possibly truncated or broken XML string. On parse errors, the well-formed content
+    /// in front of the error is converted and returned together with the error.
+    pub fn xml_to_json_partial(&self, xml: &str) -> (Value, Option<Box<dyn std::error::Error>>) {
+        let mut ctx = Context {
+            check_losses: self.strict || self.loss_callback.is_some(),
+            deadline: self.deadline_from_now(),
+            ..Default::default()
+        };
+        let xml = match self.preprocess(xml, &mut ctx) {
+            Ok(xml) => xml,
+            Err(err) => return (Value::Null, Some(err)),
+        };
+        let err = match roxmltree::Document::parse_with_options(&xml, self.parsing_options()) {
+            Ok(doc) => {
+                return match self.convert_parsed(&xml, &doc, &mut ctx) {
+                    Ok(value) => (value, None),
+                    Err(err) => (Value::Null, Some(err)),
+                }
+            }
+            Err(err) => err,
+        };
+        let end = match err {
+            // reported at the start of the document
+            roxmltree::Error::UnclosedRootNode => xml.len(),
+            _ => byte_offset(&xml, err.pos()),
+        };
+        // the repaired document is a prefix of the preprocessed one, so offsets recorded
+        // during preprocessing still apply
+        let repaired = repair_truncated(&xml, end);
+        let value = roxmltree::Document::parse_with_options(&repaired, self.parsing_options())
+            .ok()
+            .and_then(|doc| self.convert_parsed(&repaired, &doc, &mut ctx).ok());
+        (value.unwrap_or(Value::Null), Some(Box::new(err)))
+    }
+}
+
+#[test]
+fn test_repair_truncated() {
+    let xml = "<a><b x=\"1>2\">simple &amp; text</b><!-- c --><c><d>D</d><d>te&am";
+    assert_eq!(
+        repair_truncated(xml, xml.len()),
+        "<a><b x=\"1>2\">simple &amp; text</b><!-- c --><c><d>D</d><d>te</d></c></a>"
+    );
+    assert_eq!(repair_truncated("<a><b/><c x=\"", 13), "<a><b/></a>");
+    assert_eq!(repair_truncated("<a></b>", 7), "<a></a>");
+    assert_eq!(byte_offset("<a>\n<ä>", roxmltree::TextPos::new(2, 3)), 7);
+}
+
+#[test]
+fn test_xml_to_json_partial() {
+    use crate::DuplicateAttributes;
+    use serde_json::json;
+
+    let (value, err) = XmlToJson::default().xml_to_json_partial("<a><b>1</b><b>2</b><b>3");
+    assert_eq!(
+        value,
+        json!({ "b": [{ "#text": "1" }, { "#text": "2" }, { "#text": "3" }] })
+    );
+    assert!(err.is_some());
+    let (value, err) = XmlToJson::default().xml_to_json_partial("<a><b>1</b></a>");
+    assert_eq!(value, json!({ "b": { "#text": "1" } }));
+    assert!(err.is_none());
+    let (value, err) = XmlToJson::default().xml_to_json_partial("<a><b>1</b></a>garbage<");
+    assert_eq!(value, json!({ "b": { "#text": "1" } }));
+    assert!(err.is_some());
+    let parser = XmlToJson::default().with_duplicate_attributes(DuplicateAttributes::MakeArray);
+    for xml in [r#"<a x="1" x="2"><b/></a>"#, r#"<a x="1" x="2"><b>"#] {
+        let (value, _) = parser.xml_to_json_partial(xml);
+        assert_eq!(value, json!({ "@x": ["1", "2"] }));
+    }
+}
@@ -0,0 +1,197 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{Context, XmlToJson};
+use roxmltree::Node;
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+impl XmlToJson {
+    /// returns the JSON key of an element named `name` and whether it is always an array
+    fn element_key(&self, name: &str) -> (String, bool) {
+        let name = match self.case_insensitive_keys {
+            true => name.to_lowercase(),
+            false => name.to_string(),
+        };
+        match self
+            .plural_names
+            .iter()
+            .find(|(singular, _)| *singular == name)
+        {
+            Some((_, plural)) => (plural.clone(), true),
+            None => (name, false),
+        }
+    }
+
+    /// returns all child elements of `node` that are converted to the JSON key `key`
+    fn children_by_key<'a, 'input>(
+        &self,
+        node: &Node<'a, 'input>,
+        key: &str,
+    ) -> Vec<Node<'a, 'input>> {
+        node.children()
+            .filter(|child| {
+                child.is_element() && self.element_key(&self.element_name(child)).0 == key
+            })
+            .collect()
+    }
+
+    /// Translate an XML path like `/a/b[2]/@href` or `/a/b/text()` into the JSON Pointer of
+    /// the converted value in `xml`, like `/b/1/@href`. Indexes start at 1 and default to the
+    /// first element. Repeated elements and plural names are arrays, single elements are not.
+    /// Returns `None` if the path doesn't exist.
+    pub fn json_pointer(
+        &self,
+        xml: &str,
+        xml_path: &str,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let mut ctx = Context::default();
+        let xml = self.preprocess(xml, &mut ctx)?;
+        let doc = roxmltree::Document::parse_with_options(&xml, self.parsing_options())?;
+        let mut node = doc.root();
+        let mut pointer = String::new();
+        let mut steps = xml_path.trim_start_matches('/').split('/').peekable();
+        while let Some(step) = steps.next() {
+            let last = steps.peek().is_none();
+            if let Some(name) = step.strip_prefix('@') {
+                let attr_key = [self.attribute_prefix.as_str(), name].concat();
+                if !last
+                    || !node
+                        .attributes()
+                        .any(|attr| self.attribute_key(&node, &attr) == attr_key)
+                {
+                    return Ok(None);
+                }
+                pointer.extend(["/", &escape(&attr_key)]);
+                continue;
+            }
+            if step == "text()" {
+                if !last || node.text().is_none_or(|text| text.trim().is_empty()) {
+                    return Ok(None);
+                }
+                let text_name = self.text_name_for(node.tag_name().name());
+                pointer.extend(["/", &escape(text_name)]);
+                continue;
+            }
+            let (name, index) = match step.strip_suffix(']').and_then(|step| step.split_once('[')) {
+                Some((name, index)) => match index.parse::<usize>() {
+                    Ok(index) if index > 0 => (name, index),
+                    _ => return Ok(None),
+                },
+                None => (step, 1),
+            };
+            let Some(child) = node
+                .children()
+                .filter(|child| child.is_element() && self.element_name(child) == name)
+                .nth(index - 1)
+            else {
+                return Ok(None);
+            };
+            let (key, plural) = self.element_key(name);
+            if node.is_root() {
+                if self.with_root {
+                    pointer.extend(["/", &escape(&key)]);
+                }
+            } else {
+                pointer.extend(["/", &escape(&key)]);
+                let same_key = self.children_by_key(&node, &key);
+                if plural || same_key.len() > 1 {
+                    let position = same_key.iter().position(|other| *other == child);
+                    pointer.extend(["/", &position.unwrap_or_default().to_string()]);
+                }
+            }
+            node = child;
+        }
+        Ok(Some(pointer))
+    }
+
+    /// Translate a JSON Pointer into the converted value of `xml`, like `/b/1/@href`, back into
+    /// the XML path of its source, like `/a/b[2]/@href`. Returns `None` if the pointer doesn't
+    /// refer to an element, attribute or text.
+    pub fn xml_path(
+        &self,
+        xml: &str,
+        pointer: &str,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let mut ctx = Context::default();
+        let xml = self.preprocess(xml, &mut ctx)?;
+        let doc = roxmltree::Document::parse_with_options(&xml, self.parsing_options())?;
+        let mut node = doc.root_element();
+        let mut path = ["/", &self.element_name(&node)].concat();
+        let mut tokens = pointer.split('/').skip(1).map(unescape).peekable();
+        if self.with_root {
+            match tokens.next() {
+                Some(token) if token == self.element_key(&self.element_name(&node)).0 => {}
+                _ => return Ok(None),
+            }
+        }
+        while let Some(token) = tokens.next() {
+            let last = tokens.peek().is_none();
+            if token == self.text_name_for(node.tag_name().name()) {
+                return Ok(last.then(|| [&path, "/text()"].concat()));
+            }
+            if node
+                .attributes()
+                .any(|attr| self.attribute_key(&node, &attr) == token)
+            {
+                let name = &token[self.attribute_prefix.len()..];
+                return Ok(last.then(|| [&path, "/@", name].concat()));
+            }
+            let same_key = self.children_by_key(&node, &token);
+            let child = match same_key.as_slice() {
+                [] => return Ok(None),
+                [child] if !self.element_key(&self.element_name(child)).1 => *child,
+                _ => match tokens.next().and_then(|index| index.parse::<usize>().ok()) {
+                    Some(index) if index < same_key.len() => same_key[index],
+                    _ => return Ok(None),
+                },
+            };
+            let name = self.element_name(&child);
+            let same_name: Vec<_> = node
+                .children()
+                .filter(|other| other.is_element() && self.element_name(other) == name)
+                .collect();
+            path.extend(["/", &name]);
+            if same_name.len() > 1 {
+                let position = same_name.iter().position(|other| *other == child);
+                path.push_str(&format!("[{}]", position.unwrap_or_default() + 1));
+            }
+            node = child;
+        }
+        Ok(Some(path))
+    }
+}
+
+#[test]
+fn test_json_pointer() {
+    let xml = r#"<a><b href="x">t</b><b><c>1</c></b><item/></a>"#;
+    let parser = XmlToJson::default().with_plural_names(&[("item", "items")]);
+    let pointer = |path| parser.json_pointer(xml, path).unwrap();
+    assert_eq!(pointer("/a/b[2]/c").as_deref(), Some("/b/1/c"));
+    assert_eq!(pointer("/a/b/@href").as_deref(), Some("/b/0/@href"));
+    assert_eq!(pointer("/a/b/text()").as_deref(), Some("/b/0/#text"));
+    assert_eq!(pointer("/a/item").as_deref(), Some("/items/0"));
+    assert_eq!(pointer("/a/b[3]"), None);
+    let path = |pointer| parser.xml_path(xml, pointer).unwrap();
+    assert_eq!(path("/b/1/c").as_deref(), Some("/a/b[2]/c"));
+    assert_eq!(path("/b/0/@href").as_deref(), Some("/a/b[1]/@href"));
+    assert_eq!(path("/items/0").as_deref(), Some("/a/item"));
+    assert_eq!(path("/b/2"), None);
+    let with_root = XmlToJson::default().with_root();
+    assert_eq!(
+        with_root.json_pointer(xml, "/a/b[2]/c").unwrap().as_deref(),
+        Some("/a/b/1/c")
+    );
+    assert_eq!(
+        with_root.xml_path(xml, "/a/b/1/c").unwrap().as_deref(),
+        Some("/a/b[2]/c")
+    );
+}
@@ -0,0 +1,41 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::Value;
+
+impl XmlToJson {
+    /// convert the attributes `names` to `true` if they are empty or repeat their name,
+    /// like `disabled=""` or `checked="checked"` in XHTML and SVG. Other values are kept.
+    /// Names are given without attribute prefix.
+    pub fn with_presence_attributes(mut self, names: &[&str]) -> Self {
+        self.presence_attributes = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// `true` if attribute `name` is a presence attribute that is only present
+    pub(crate) fn presence_value(&self, name: &str, value: &str) -> Option<Value> {
+        let value = value.trim();
+        (self
+            .presence_attributes
+            .iter()
+            .any(|presence| presence == name)
+            && (value.is_empty() || value.eq_ignore_ascii_case(name)))
+        .then_some(Value::Bool(true))
+    }
+}
+
+#[test]
+fn test_presence_attributes() {
+    use serde_json::json;
+
+    let xml = r#"<form><input disabled="" checked="checked" selected="no" value=""/></form>"#;
+    let parser =
+        XmlToJson::default().with_presence_attributes(&["disabled", "checked", "selected"]);
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({ "input": { "@disabled": true, "@checked": true, "@selected": "no", "@value": "" } })
+    );
+}
@@ -0,0 +1,65 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{NullPolicy, XmlToJson};
+
+/// A versioned set of defaults, see `XmlToJson::with_profile`. Pin a profile to keep the
+/// output stable when the defaults of a later release change.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub enum ConversionProfile {
+    /// the defaults of the first release: the defaults of `XmlToJson::default()`, but
+    /// without the nesting depth limit, so deeply nested documents are accepted as before
+    /// and may overflow the stack
+    #[default]
+    V1,
+    /// empty elements are converted to empty objects instead of being skipped
+    /// (`NullPolicy::Never`), whitespace-only text is dropped (`with_canonicalization`)
+    /// and documents nested deeper than 256 elements are rejected
+    V2,
+}
+
+impl XmlToJson {
+    /// set the defaults of `profile`. Call it before other builder methods,
+    /// as it overrides the options that the profile defines.
+    pub fn with_profile(mut self, profile: ConversionProfile) -> Self {
+        let defaults = XmlToJson::default();
+        (self.null_policy, self.canonicalize, self.max_nesting_depth) = match profile {
+            ConversionProfile::V1 => (defaults.null_policy, defaults.canonicalize, usize::MAX),
+            ConversionProfile::V2 => (NullPolicy::Never, true, defaults.max_nesting_depth),
+        };
+        self
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_conversion_profile() {
+    use serde_json::json;
+
+    let xml = "<a><b> </b><c/></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_profile(ConversionProfile::V1)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b": { "#text": "" } })
+    );
+    assert_eq!(
+        XmlToJson::default()
+            .with_profile(ConversionProfile::V2)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b": {}, "c": {} })
+    );
+    let nested = format!("{}{}", "<a>".repeat(300), "</a>".repeat(300));
+    assert!(XmlToJson::default().xml_to_json(&nested).is_err());
+    assert!(XmlToJson::default()
+        .with_profile(ConversionProfile::V2)
+        .xml_to_json(&nested)
+        .is_err());
+    let v1 = XmlToJson::default().with_profile(ConversionProfile::V1);
+    assert!(v1.check_nesting_depth(&nested).is_ok());
+}
@@ -0,0 +1,75 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::{Map, Value};
+
+/// Defines what happens if a promoted attribute has the same name as a child element,
+/// see `XmlToJson::with_promotion_conflict`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PromotionConflict {
+    /// keep the attribute with its prefix
+    #[default]
+    KeepPrefix,
+    /// keep the child element and drop the attribute
+    PreferChild,
+    /// replace the child element by the attribute
+    PreferAttribute,
+}
+
+impl XmlToJson {
+    /// convert the attributes `names` like child elements without attribute prefix,
+    /// e.g. `{"id": "5"}` instead of `{"@id": "5"}`. Names are given without prefix.
+    /// XML written from the JSON contains child elements instead of these attributes.
+    pub fn with_promote_attributes(mut self, names: &[&str]) -> Self {
+        self.promoted_attributes = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// what happens if a promoted attribute has the same name as a child element,
+    /// the default is `PromotionConflict::KeepPrefix`
+    pub fn with_promotion_conflict(mut self, conflict: PromotionConflict) -> Self {
+        self.promotion_conflict = conflict;
+        self
+    }
+
+    /// remove the attribute prefix of promoted attributes
+    pub(crate) fn promote_attributes(&self, elements: &mut Map<String, Value>) {
+        for name in &self.promoted_attributes {
+            let key = [self.attribute_prefix.as_str(), name].concat();
+            let Some(val) = elements.remove(&key) else {
+                continue;
+            };
+            match (elements.contains_key(name), self.promotion_conflict) {
+                (false, _) | (true, PromotionConflict::PreferAttribute) => {
+                    elements.insert(name.clone(), val);
+                }
+                (true, PromotionConflict::KeepPrefix) => {
+                    elements.insert(key, val);
+                }
+                (true, PromotionConflict::PreferChild) => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn test_promote_attributes() {
+    use serde_json::json;
+
+    let xml = r#"<a id="5" type="t" lang="en"><type>child</type></a>"#;
+    let parser = XmlToJson::default().with_promote_attributes(&["id", "type"]);
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({ "id": "5", "@type": "t", "@lang": "en", "type": { "#text": "child" } })
+    );
+    assert_eq!(
+        parser
+            .with_promotion_conflict(PromotionConflict::PreferAttribute)
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "id": "5", "type": "t", "@lang": "en" })
+    );
+}
@@ -0,0 +1,119 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::Value;
+
+enum Step {
+    Key(String),
+    AnyKey,
+    Index(usize),
+    AnyIndex,
+}
+
+impl XmlToJson {
+    /// Query a converted value with a small JSONPath subset, for example `$.b[*].c.d.#text`.
+    ///
+    /// Supported are `.key`, `['key']`, `.*`, `[n]` and `[*]`. `.text()` is a shorthand for the
+    /// configured text name. As repeated XML elements may or may not be converted to arrays,
+    /// key steps look into arrays and index steps also match single values.
+    pub fn query<'a>(
+        &self,
+        value: &'a Value,
+        path: &str,
+    ) -> Result<Vec<&'a Value>, Box<dyn std::error::Error>> {
+        let mut current = vec![value];
+        for step in self.parse_query(path)? {
+            current = current
+                .into_iter()
+                .flat_map(|val| Self::query_step(val, &step))
+                .collect();
+        }
+        Ok(current)
+    }
+
+    fn parse_query(&self, path: &str) -> Result<Vec<Step>, Box<dyn std::error::Error>> {
+        let mut steps = Vec::new();
+        let mut rest = path.strip_prefix('$').unwrap_or(path);
+        while !rest.is_empty() {
+            if let Some(after_dot) = rest.strip_prefix('.') {
+                let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+                let key = &after_dot[..end];
+                steps.push(match key {
+                    "" => return Err(format!("empty key in query '{path}'").into()),
+                    "*" => Step::AnyKey,
+                    "text()" => Step::Key(self.text_name.clone()),
+                    _ => Step::Key(key.to_string()),
+                });
+                rest = &after_dot[end..];
+            } else if let Some(after_bracket) = rest.strip_prefix('[') {
+                let end = after_bracket
+                    .find(']')
+                    .ok_or_else(|| format!("missing ']' in query '{path}'"))?;
+                let inner = &after_bracket[..end];
+                steps.push(if inner == "*" {
+                    Step::AnyIndex
+                } else if let Some(key) = inner
+                    .strip_prefix('\'')
+                    .and_then(|key| key.strip_suffix('\''))
+                {
+                    Step::Key(key.to_string())
+                } else {
+                    Step::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| format!("invalid index '{inner}' in query '{path}'"))?,
+                    )
+                });
+                rest = &after_bracket[end + 1..];
+            } else {
+                return Err(format!("unexpected '{rest}' in query '{path}'").into());
+            }
+        }
+        Ok(steps)
+    }
+
+    fn query_step<'a>(value: &'a Value, step: &Step) -> Vec<&'a Value> {
+        match (value, step) {
+            (Value::Array(array), Step::Key(_) | Step::AnyKey) => array
+                .iter()
+                .flat_map(|val| Self::query_step(val, step))
+                .collect(),
+            (Value::Object(map), Step::Key(key)) => map.get(key).into_iter().collect(),
+            (Value::Object(map), Step::AnyKey) => map.values().collect(),
+            (Value::Array(array), Step::Index(index)) => array.get(*index).into_iter().collect(),
+            (Value::Array(array), Step::AnyIndex) => array.iter().collect(),
+            (_, Step::Index(0) | Step::AnyIndex) => vec![value],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[test]
+fn test_query() {
+    use serde_json::json;
+
+    let xml =
+        "<a><b href=\"#self\">simple</b><b><c class=\"my_class\"><d>D</d><d>1</d></c></b></a>";
+    let parser = XmlToJson::default();
+    let value = parser.xml_to_json(xml).unwrap();
+    assert_eq!(
+        parser.query(&value, "$.b[*].c.d.#text").unwrap(),
+        vec![&json!("D"), &json!("1")]
+    );
+    assert_eq!(
+        parser.query(&value, "$.b[0].@href").unwrap(),
+        vec![&json!("#self")]
+    );
+    assert_eq!(
+        parser.query(&value, "$.b.c.d[1].text()").unwrap(),
+        vec![&json!("1")]
+    );
+    assert_eq!(
+        parser.query(&value, "$.b.c['@class']").unwrap(),
+        vec![&json!("my_class")]
+    );
+    assert!(parser.query(&value, "$.b[x]").is_err());
+}
@@ -0,0 +1,296 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+//! quick-xml backend, an event based parser for the basic options
+
+use crate::{AttributeNormalization, Context, NamespaceMode, NullPolicy, XmlToJson};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+
+/// an element that is currently parsed
+struct OpenElement {
+    name: String,
+    path_len: usize,
+    elements: Map<String, Value>,
+    attribute_count: usize,
+    /// number of child elements so far
+    children: usize,
+    text: String,
+    /// whether a text node was seen, even if it is only whitespace
+    has_text: bool,
+    /// text is only collected in front of the first child element, comment or PI
+    text_closed: bool,
+}
+
+fn close_text(stack: &mut [OpenElement]) {
+    if let Some(element) = stack.last_mut() {
+        element.text_closed = true;
+    }
+}
+
+/// quick-xml doesn't reject a second element after the root, roxmltree does
+fn check_single_root(stack: &[OpenElement], has_root: bool) -> Result<(), &'static str> {
+    match stack.is_empty() && has_root {
+        true => Err("multiple root elements"),
+        false => Ok(()),
+    }
+}
+
+impl XmlToJson {
+    /// Parse XML string with quick-xml and return serde_json Value.
+    ///
+    /// Supported are the root and text name, attribute prefix, plural names, flattened text
+    /// arrays, null policy for empty elements, type overrides, entities and key renames.
+    /// Namespaces are either stripped or kept with their prefix. All other options require the
+    /// default `roxmltree` backend.
+    pub fn xml_to_json_with_quick_xml(
+        &self,
+        xml: &str,
+    ) -> Result<Value, Box<dyn std::error::Error>> {
+        let mut ctx = Context {
+            deadline: self.deadline_from_now(),
+            ..Default::default()
+        };
+        self.traced(xml.len(), &mut ctx, |ctx| self.convert_quick(xml, ctx))
+    }
+
+    fn convert_quick(
+        &self,
+        xml: &str,
+        ctx: &mut Context,
+    ) -> Result<Value, Box<dyn std::error::Error>> {
+        let xml = self.preprocess(xml, ctx)?;
+        let mut reader = Reader::from_str(&xml);
+        let mut stack: Vec<OpenElement> = Vec::new();
+        let mut root = None;
+        loop {
+            match reader.read_event()? {
+                Event::Start(start) => {
+                    check_single_root(&stack, root.is_some())?;
+                    close_text(&mut stack);
+                    let element = self.open_element(&start, ctx)?;
+                    stack.push(element);
+                }
+                Event::Empty(start) => {
+                    check_single_root(&stack, root.is_some())?;
+                    close_text(&mut stack);
+                    let element = self.open_element(&start, ctx)?;
+                    self.close_element(element, &mut stack, ctx, &mut root)?;
+                }
+                Event::End(_) => {
+                    let element = stack.pop().ok_or("unexpected end tag")?;
+                    self.close_element(element, &mut stack, ctx, &mut root)?;
+                }
+                Event::Text(text)
+                    if stack.is_empty() && !text.iter().all(u8::is_ascii_whitespace) =>
+                {
+                    return Err("text outside of the root element".into());
+                }
+                Event::Text(_) if stack.is_empty() => {}
+                Event::Text(text) => {
+                    if let Some(element) = stack.last_mut().filter(|element| !element.text_closed) {
+                        element.text.push_str(&text.unescape()?);
+                        element.has_text = true;
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some(element) = stack.last_mut().filter(|element| !element.text_closed) {
+                        element.text.push_str(std::str::from_utf8(&data)?);
+                        element.has_text = true;
+                    }
+                }
+                Event::Comment(_) | Event::PI(_) => close_text(&mut stack),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        if !stack.is_empty() {
+            return Err("unclosed root element".into());
+        }
+        let (name, value) = root.ok_or("no root element")?;
+        let value = if self.with_root {
+            let mut map = Map::new();
+            map.insert(name.clone(), value.unwrap_or(Value::Null));
+            Value::Object(map)
+        } else {
+            value.unwrap_or(Value::Null)
+        };
+        let value = self.rename_keys(self.apply_template(&name, value));
+        Ok(self.flatten_output(value))
+    }
+
+    fn quick_name(&self, qname: &[u8], local_name: &[u8]) -> Result<String, std::str::Utf8Error> {
+        let name = match self.namespace_mode {
+            NamespaceMode::Strip => local_name,
+            _ => qname,
+        };
+        Ok(std::str::from_utf8(name)?.to_string())
+    }
+
+    fn open_element(
+        &self,
+        start: &BytesStart,
+        ctx: &mut Context,
+    ) -> Result<OpenElement, Box<dyn std::error::Error>> {
+        let name = self.quick_name(start.name().as_ref(), start.local_name().as_ref())?;
+        let path_len = ctx.enter(&name);
+        self.check_cancelled(ctx)?;
+        ctx.elements += 1;
+        if self.max_attributes.is_some() || self.max_name_length.is_some() {
+            let names = start
+                .attributes()
+                .map(|attr| Ok(std::str::from_utf8(attr?.key.into_inner())?.to_string()))
+                .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+            let all_names = std::iter::once(name.as_str()).chain(names.iter().map(String::as_str));
+            self.validate_element(ctx, names.len(), all_names)?;
+        }
+        let mut elements = Map::new();
+        for attr in start.attributes() {
+            let attr = attr?;
+            let qname = attr.key.as_ref();
+            if qname == b"xmlns" || qname.starts_with(b"xmlns:") {
+                let prefix = qname.strip_prefix(b"xmlns:").map(std::str::from_utf8);
+                let uri = attr.unescape_value()?;
+                self.insert_namespace_declaration(&mut elements, prefix.transpose()?, &uri);
+                continue;
+            }
+            let key = [
+                self.attribute_prefix.as_str(),
+                &self.quick_name(qname, attr.key.local_name().as_ref())?,
+            ]
+            .concat();
+            let path = [&ctx.path, "/", &key].concat();
+            let value = match self.attribute_normalization {
+                AttributeNormalization::Raw => attr.unescape_value()?,
+                AttributeNormalization::Spec => {
+                    let raw = std::str::from_utf8(&attr.value)?.replace(['\t', '\n', '\r'], " ");
+                    Cow::Owned(quick_xml::escape::unescape(&raw)?.into_owned())
+                }
+            };
+            let name = &key[self.attribute_prefix.len()..];
+            let val = match self.presence_value(name, &value) {
+                Some(presence) => presence,
+                None => self.typed_value(&path, self.attribute_value(&value))?,
+            };
+            elements.insert(key, val);
+        }
+        Ok(OpenElement {
+            name,
+            path_len,
+            attribute_count: elements.len(),
+            elements,
+            children: 0,
+            text: String::new(),
+            has_text: false,
+            text_closed: false,
+        })
+    }
+
+    fn close_element(
+        &self,
+        mut element: OpenElement,
+        stack: &mut [OpenElement],
+        ctx: &mut Context,
+        root: &mut Option<(String, Option<Value>)>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // canonicalization drops whitespace-only text like `element_text` of roxml.rs
+        let has_text = element.has_text && !(self.canonicalize && element.text.trim().is_empty());
+        if has_text {
+            let text = element.text.trim();
+            let val = match self.large_text(&ctx.path, text) {
+                Some(placeholder) => placeholder,
+                None => self.typed_value(&ctx.path, text)?,
+            };
+            let text_name = self.text_name_for(&element.name).to_string();
+            element.elements.insert(text_name, val);
+        }
+        if !has_text
+            && element.attribute_count > 0
+            && element.elements.len() == element.attribute_count
+        {
+            element.elements = self.compact_attributes(&element.name, element.elements);
+        }
+        self.promote_attributes(&mut element.elements);
+        self.deduplicate_siblings(&mut element.elements);
+        if self.flatten_text_arrays || self.inline_simple_children.is_some() {
+            self.flatten_text_arrays(&mut element.elements);
+        }
+        ctx.leave(element.path_len);
+        let value = if !element.elements.is_empty() {
+            Some(Value::Object(element.elements))
+        } else {
+            match self.null_policy {
+                NullPolicy::Null => Some(Value::Null),
+                NullPolicy::Never => Some(Value::Object(Map::new())),
+                NullPolicy::Skip | NullPolicy::XsiNil => None,
+            }
+        };
+        match stack.last_mut() {
+            Some(parent) => {
+                parent.children += 1;
+                if let Some(mut value) = value {
+                    self.set_sibling_index(&mut value, parent.children - 1);
+                    self.insert_child(&mut parent.elements, &element.name, value, false, ctx)?;
+                }
+            }
+            None => *root = Some((element.name, value)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_quick_xml_backend() {
+    let docs = [
+        "<a id=\"1\">x<!-- c -->y<b>1</b><b>2</b><c/></a>",
+        "<a xmlns:n=\"urn:n\"><n:b n:id=\"&lt;\">t<![CDATA[ & ]]></n:b><item>1</item></a>",
+        "<a><b>text</b><b><c>1</c></b><b/></a>",
+        "<a>text<h>  </h><h>  </h></a>",
+        "<a>\n  <b id=\"1\">\n    <c> </c>\n  </b>\n  <b/>\n</a>\n",
+        "<a> <![CDATA[ ]]> <b x=\"1\"> </b></a>",
+    ];
+    let parsers = [
+        XmlToJson::default(),
+        XmlToJson::default()
+            .with_root()
+            .with_plural_names(&[("item", "items")])
+            .with_null_policy(NullPolicy::Null)
+            .with_sibling_index(),
+        XmlToJson::default()
+            .with_text_name("value")
+            .with_attribute_prefix("_")
+            .with_flatten_text_arrays()
+            .with_namespace_declarations(crate::NamespaceDeclarations::Collect),
+        XmlToJson::default().with_canonicalization(),
+    ];
+    for parser in &parsers {
+        for doc in docs {
+            assert_eq!(
+                parser.xml_to_json_with_quick_xml(doc).unwrap(),
+                parser.xml_to_json(doc).unwrap(),
+                "{doc}"
+            );
+        }
+    }
+    assert!(XmlToJson::default()
+        .xml_to_json_with_quick_xml("<a><b></a>")
+        .is_err());
+}
+
+#[test]
+fn test_quick_xml_single_root() {
+    let parser = XmlToJson::default();
+    assert!(parser
+        .xml_to_json_with_quick_xml("<a/>\n<!-- c -->\n")
+        .is_ok());
+    for xml in ["<a/><b/>", "<a>1</a><a>2</a>", "<a/>text", "text<a/>"] {
+        assert!(parser.xml_to_json_with_quick_xml(xml).is_err(), "{xml}");
+        #[cfg(feature = "roxmltree")]
+        assert!(parser.xml_to_json(xml).is_err(), "{xml}");
+    }
+}
@@ -0,0 +1,220 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::{Map, Value};
+use std::io::Write;
+
+/// quote a CSV field if it contains the delimiter, a quote or a line break
+fn csv_field(val: &Value, delimiter: char) -> String {
+    let text = match val {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    if text.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+impl XmlToJson {
+    /// Convert `xml` and flatten each record matched by the query `record_path` into a single
+    /// level map, for CSV or DataFrame export. Keys are joined by `.`, like `c.d` or `@href`,
+    /// see `with_key_separator`.
+    /// Texts are stored under the key of their element, array items under their index.
+    pub fn to_records(
+        &self,
+        xml: &str,
+        record_path: &str,
+    ) -> Result<Vec<Map<String, Value>>, Box<dyn std::error::Error>> {
+        let value = self.xml_to_json(xml)?;
+        let records = self.query(&value, record_path)?;
+        Ok(records
+            .into_iter()
+            .map(|record| {
+                let mut row = Map::new();
+                self.flatten_record("", record, &mut row);
+                row
+            })
+            .collect())
+    }
+
+    /// join the keys of nested values by `separator` in `to_records` and `xml_to_csv`,
+    /// default is `.`
+    pub fn with_key_separator(mut self, separator: &str) -> Self {
+        self.key_separator = String::from(separator);
+        self
+    }
+
+    /// use `delimiter` instead of `,` in `xml_to_csv`
+    pub fn with_csv_delimiter(mut self, delimiter: char) -> Self {
+        self.csv_delimiter = delimiter;
+        self
+    }
+
+    /// Write the records matched by the query `record_path` as CSV, flattened like
+    /// `to_records`. The header contains the keys of all records in order of appearance.
+    pub fn xml_to_csv(
+        &self,
+        xml: &str,
+        record_path: &str,
+        mut writer: impl Write,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let records = self.to_records(xml, record_path)?;
+        let mut header: Vec<&String> = Vec::new();
+        for key in records.iter().flat_map(Map::keys) {
+            if !header.contains(&key) {
+                header.push(key);
+            }
+        }
+        let delimiter = self.csv_delimiter.to_string();
+        let line: Vec<_> = header
+            .iter()
+            .map(|key| csv_field(&Value::String(key.to_string()), self.csv_delimiter))
+            .collect();
+        writeln!(writer, "{}", line.join(&delimiter))?;
+        for record in &records {
+            let line: Vec<_> = header
+                .iter()
+                .map(|key| csv_field(record.get(*key).unwrap_or(&Value::Null), self.csv_delimiter))
+                .collect();
+            writeln!(writer, "{}", line.join(&delimiter))?;
+        }
+        Ok(())
+    }
+
+    /// Produce a single level object with path keys joined by `separator`, like
+    /// `{"b.c.d": "D", "b.@href": "#self"}`. Array items are written as `b[1].c`, counted
+    /// from 0. Texts are stored under the path of their element.
+    pub fn with_flatten(mut self, separator: &str) -> Self {
+        self.flatten_separator = Some(separator.to_string());
+        self
+    }
+
+    /// flatten the converted `value` if `with_flatten` is set
+    pub(crate) fn flatten_output(&self, value: Value) -> Value {
+        let Some(separator) = &self.flatten_separator else {
+            return value;
+        };
+        let mut flat = Map::new();
+        self.flatten_path("", value, separator, &mut flat);
+        Value::Object(flat)
+    }
+
+    fn flatten_path(
+        &self,
+        prefix: &str,
+        value: Value,
+        separator: &str,
+        flat: &mut Map<String, Value>,
+    ) {
+        match value {
+            Value::Object(map) => {
+                for (key, val) in map {
+                    if key == self.text_name && !prefix.is_empty() {
+                        self.flatten_path(prefix, val, separator, flat);
+                    } else if prefix.is_empty() {
+                        self.flatten_path(&key, val, separator, flat);
+                    } else {
+                        self.flatten_path(
+                            &[prefix, separator, &key].concat(),
+                            val,
+                            separator,
+                            flat,
+                        );
+                    }
+                }
+            }
+            Value::Array(array) => {
+                for (index, val) in array.into_iter().enumerate() {
+                    self.flatten_path(&format!("{prefix}[{index}]"), val, separator, flat);
+                }
+            }
+            _ if prefix.is_empty() => {
+                flat.insert(self.text_name.clone(), value);
+            }
+            _ => {
+                flat.insert(prefix.to_string(), value);
+            }
+        }
+    }
+
+    fn flatten_record(&self, prefix: &str, value: &Value, row: &mut Map<String, Value>) {
+        let join = |key: &str| match prefix {
+            "" => key.to_string(),
+            _ => [prefix, &self.key_separator, key].concat(),
+        };
+        match value {
+            Value::Object(map) => {
+                for (key, val) in map {
+                    if key == &self.text_name && !prefix.is_empty() {
+                        self.flatten_record(prefix, val, row);
+                    } else {
+                        self.flatten_record(&join(key), val, row);
+                    }
+                }
+            }
+            Value::Array(array) => {
+                for (index, val) in array.iter().enumerate() {
+                    self.flatten_record(&join(&index.to_string()), val, row);
+                }
+            }
+            _ if prefix.is_empty() => {
+                row.insert(self.text_name.clone(), value.clone());
+            }
+            _ => {
+                row.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_to_records() {
+    use serde_json::json;
+
+    let xml =
+        r#"<a><b href="x">text<c><d>1</d></c></b><b href="y"><c><d>2</d><d>3</d></c></b></a>"#;
+    let records = XmlToJson::default().to_records(xml, "$.b[*]").unwrap();
+    assert_eq!(
+        records,
+        [
+            json!({ "@href": "x", "#text": "text", "c.d": "1" }),
+            json!({ "@href": "y", "c.d.0": "2", "c.d.1": "3" }),
+        ]
+        .map(|record| record.as_object().unwrap().clone())
+    );
+}
+
+#[test]
+fn test_xml_to_csv() {
+    let xml = r#"<a><b href="x">te"xt<c><d>1</d></c></b><b><c><d>2;3</d></c><e>4</e></b></a>"#;
+    let mut csv = Vec::new();
+    XmlToJson::default()
+        .with_csv_delimiter(';')
+        .with_key_separator("_")
+        .xml_to_csv(xml, "$.b[*]", &mut csv)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "#text;@href;c_d;e\n\"te\"\"xt\";x;1;\n;;\"2;3\";4\n"
+    );
+}
+
+#[test]
+fn test_flatten() {
+    use serde_json::json;
+
+    let xml = "<a><b href=\"#self\"><c><d>D</d></c></b><e>1</e><e><f>2</f></e></a>";
+    assert_eq!(
+        XmlToJson::default()
+            .with_flatten(".")
+            .xml_to_json(xml)
+            .unwrap(),
+        json!({ "b.@href": "#self", "b.c.d": "D", "e[0]": "1", "e[1].f": "2" })
+    );
+}
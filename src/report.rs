@@ -0,0 +1,116 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use std::fmt;
+
+/// The kind of information that got lost during conversion
+#[derive(Clone, Debug, PartialEq)]
+pub enum LossKind {
+    /// the namespace of an element or attribute was dropped
+    Namespace(String),
+    /// significant leading or trailing whitespace was trimmed
+    TrimmedWhitespace,
+    /// a text node besides the first one of a mixed content element was dropped
+    MixedContent(String),
+    /// an element was grouped with an earlier sibling of the same name,
+    /// so the order relative to its other siblings is lost
+    SiblingOrder(String),
+    /// a key was used twice on the same element, contains the key and the overwritten value
+    Collision(String, String),
+    /// a comment was ignored
+    Comment,
+    /// a processing instruction was ignored
+    ProcessingInstruction(String),
+}
+
+/// A single place where information was lost, `path` is the XML path of the element
+#[derive(Clone, Debug, PartialEq)]
+pub struct Loss {
+    pub path: String,
+    pub kind: LossKind,
+}
+
+impl fmt::Display for LossKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LossKind::Namespace(uri) => write!(f, "namespace '{uri}' dropped"),
+            LossKind::TrimmedWhitespace => write!(f, "whitespace trimmed"),
+            LossKind::MixedContent(text) => write!(f, "mixed content text '{text}' dropped"),
+            LossKind::SiblingOrder(name) => write!(f, "order of sibling '{name}' lost"),
+            LossKind::Collision(key, old_val) => {
+                write!(f, "value '{old_val}' of key '{key}' overwritten")
+            }
+            LossKind::Comment => write!(f, "comment ignored"),
+            LossKind::ProcessingInstruction(target) => {
+                write!(f, "processing instruction '{target}' ignored")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Loss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "lossy conversion at '{}': {}", self.path, self.kind)
+    }
+}
+
+impl std::error::Error for Loss {}
+
+/// A non-fatal issue that doesn't fit into the document structure, see `Warning`
+#[derive(Clone, Debug, PartialEq)]
+pub enum WarningKind {
+    /// an unknown entity was kept as text, see `XmlToJson::with_unknown_entities_as_text`
+    UnknownEntity(String),
+    /// the output exceeded `max_output_bytes` and the remaining children were dropped
+    Truncated,
+    /// the element exceeded `max_convert_depth` and was kept as raw XML
+    DepthLimit,
+    /// an illegally repeated attribute was tolerated
+    DuplicateAttribute(String),
+    /// an invalid UTF-8 sequence at this byte offset of the input was replaced or skipped,
+    /// see `XmlToJson::with_utf8_policy`
+    InvalidUtf8(usize),
+}
+
+/// A single non-fatal issue, `path` is the XML path of the element or empty for issues found
+/// before parsing
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    pub path: String,
+    pub kind: WarningKind,
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarningKind::UnknownEntity(name) => write!(f, "unknown entity '{name}' kept as text"),
+            WarningKind::Truncated => write!(f, "output truncated"),
+            WarningKind::DepthLimit => write!(f, "depth limit reached, kept as raw XML"),
+            WarningKind::DuplicateAttribute(name) => write!(f, "duplicate attribute '{name}'"),
+            WarningKind::InvalidUtf8(offset) => write!(f, "invalid UTF-8 at byte {offset}"),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "warning at '{}': {}", self.path, self.kind)
+    }
+}
+
+/// Collects all places where the conversion lost information, and other non-fatal issues.
+/// Dropped namespaces and overwritten keys are reported as losses.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConversionReport {
+    pub losses: Vec<Loss>,
+    pub warnings: Vec<Warning>,
+}
+
+impl ConversionReport {
+    /// returns true if the conversion didn't lose any information
+    pub fn is_lossless(&self) -> bool {
+        self.losses.is_empty()
+    }
+}
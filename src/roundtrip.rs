@@ -0,0 +1,97 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{DiffEntry, XmlToJson};
+use std::fmt;
+
+/// Returned by `XmlToJson::verify_round_trip` if a document doesn't survive the conversion
+/// to JSON and back
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoundTripReport {
+    /// the XML written from the JSON, empty if the conversion failed
+    pub xml: String,
+    /// differences between the original and the written document, see `XmlToJson::diff`
+    pub differences: Vec<DiffEntry>,
+    /// the error if the document couldn't be converted, written or parsed again
+    pub error: Option<String>,
+}
+
+impl fmt::Display for RoundTripReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.error, self.differences.first()) {
+            (Some(error), _) => write!(f, "round trip failed: {error}"),
+            (None, Some(first)) => write!(
+                f,
+                "round trip changed {} values, the first at '{}'",
+                self.differences.len(),
+                first.path
+            ),
+            (None, None) => write!(f, "round trip succeeded"),
+        }
+    }
+}
+
+impl std::error::Error for RoundTripReport {}
+
+impl XmlToJson {
+    /// Check that `xml` survives the conversion with this configuration: it is converted to
+    /// JSON, written back to XML and both documents are compared with the default options
+    /// and `with_canonicalization`, so indentation doesn't matter.
+    /// Use it on sample documents to verify that a configuration is lossless for them.
+    pub fn verify_round_trip(&self, xml: &str) -> Result<(), RoundTripReport> {
+        let failed = |xml: String, error: Box<dyn std::error::Error>| RoundTripReport {
+            xml,
+            differences: Vec::new(),
+            error: Some(error.to_string()),
+        };
+        let reference = XmlToJson::default().with_root().with_canonicalization();
+        let original = reference
+            .xml_to_json(xml)
+            .map_err(|err| failed(String::new(), err))?;
+        let root_name = match (self.with_root, original.as_object()) {
+            (false, Some(root)) => root.keys().next().map_or("", String::as_str),
+            _ => "",
+        };
+        let written = self
+            .xml_to_json(xml)
+            .and_then(|value| self.json_to_xml_pretty(&value, root_name, 0))
+            .map_err(|err| failed(String::new(), err))?;
+        let parsed = match reference.xml_to_json(&written) {
+            Ok(parsed) => parsed,
+            Err(err) => return Err(failed(written, err)),
+        };
+        let mut differences = Vec::new();
+        Self::diff_values(&original, &parsed, &mut String::new(), &mut differences);
+        if differences.is_empty() {
+            return Ok(());
+        }
+        Err(RoundTripReport {
+            xml: written,
+            differences,
+            error: None,
+        })
+    }
+}
+
+#[test]
+fn test_verify_round_trip() {
+    let xml = r#"<a id="1"><b>x</b><b>y</b><c lang="en">z</c></a>"#;
+    assert_eq!(XmlToJson::default().verify_round_trip(xml), Ok(()));
+    assert_eq!(
+        XmlToJson::default().with_root().verify_round_trip(xml),
+        Ok(())
+    );
+    let report = XmlToJson::default()
+        .with_promote_attributes(&["lang"])
+        .verify_round_trip(xml)
+        .unwrap_err();
+    assert_eq!(report.differences.len(), 2);
+    assert_eq!(report.differences[0].path, "/a/c/@lang");
+    assert!(XmlToJson::default()
+        .verify_round_trip("<a>")
+        .unwrap_err()
+        .error
+        .is_some());
+}
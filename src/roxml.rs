@@ -0,0 +1,796 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+//! roxmltree backend, the default and fully featured parser
+
+use crate::{
+    declaration, emit_value, ArrayMode, AttributeNormalization, Context, ConversionReport,
+    DuplicateAttributes, ElementAction, JsonSink, LossKind, NamespaceDeclarations, NamespaceMode,
+    NullPolicy, TraceEvent, WarningKind, XmlToJson,
+};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Namespace of `xsi:nil`
+const NS_XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// the start of a converted element, see `XmlToJson::element_start`
+pub(crate) enum ElementStart<'a> {
+    /// the element is converted to this value without its children
+    Done(Value),
+    /// the text, attribute and namespace entries, and the key of the text if there is one
+    Entries(Map<String, Value>, Option<&'a str>),
+}
+
+/// resolves the predefined entities and character references of a raw attribute value,
+/// None if it references other entities
+fn unescape_attribute(raw: &str) -> Option<String> {
+    let mut value = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(pos) = rest.find('&') {
+        value.push_str(&rest[..pos]);
+        let end = pos + rest[pos..].find(';')?;
+        let c = match &rest[pos + 1..end] {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "apos" => '\'',
+            "quot" => '"',
+            reference => {
+                let code = match reference.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => reference.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+        value.push(c);
+        rest = &rest[end + 1..];
+    }
+    value.push_str(rest);
+    Some(value)
+}
+
+impl XmlToJson {
+    /// Convert an already parsed roxmltree element, for example a selected subtree,
+    /// without serializing and parsing it again
+    pub fn node_to_json(
+        &self,
+        node: &roxmltree::Node,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        let mut ctx = Context::default();
+        let value = self.parse_root(node, &mut ctx)?;
+        Ok(value.map(|value| self.rename_keys(value)))
+    }
+
+    /// Parse XML string and return serde_json Value together with a report of all
+    /// places where information was lost during conversion
+    pub fn xml_to_json_with_report(
+        &self,
+        xml: &str,
+    ) -> Result<(Value, ConversionReport), Box<dyn std::error::Error>> {
+        self.convert(xml, true)
+    }
+
+    pub(crate) fn convert(
+        &self,
+        xml: &str,
+        check_losses: bool,
+    ) -> Result<(Value, ConversionReport), Box<dyn std::error::Error>> {
+        let mut ctx = Context {
+            check_losses,
+            ..Default::default()
+        };
+        let value = self.convert_with_context(xml, &mut ctx)?;
+        Ok((value, ctx.report))
+    }
+
+    pub(crate) fn convert_with_context(
+        &self,
+        xml: &str,
+        ctx: &mut Context,
+    ) -> Result<Value, Box<dyn std::error::Error>> {
+        self.traced(xml.len(), ctx, |ctx| self.convert_document(xml, ctx))
+    }
+
+    fn convert_document(
+        &self,
+        xml: &str,
+        ctx: &mut Context,
+    ) -> Result<Value, Box<dyn std::error::Error>> {
+        ctx.deadline = self.deadline_from_now();
+        let xml = self.preprocess(xml, ctx)?;
+        let doc = roxmltree::Document::parse_with_options(&xml, self.parsing_options())?;
+        self.convert_parsed(&xml, &doc, ctx)
+    }
+
+    /// convert `doc`, which was parsed from the preprocessed `xml`
+    pub(crate) fn convert_parsed(
+        &self,
+        xml: &str,
+        doc: &roxmltree::Document,
+        ctx: &mut Context,
+    ) -> Result<Value, Box<dyn std::error::Error>> {
+        let root = doc.root_element();
+        if ctx.check_losses {
+            for node in doc.root().children().filter(|node| *node != root) {
+                self.check_ignored_node(&node, ctx);
+            }
+        }
+        let value = self.parse_root(&root, ctx)?.unwrap_or(Value::Null);
+        let root_name = self.element_name(&root);
+        let mut value = self.apply_template(&root_name, value);
+        if self.with_declaration {
+            let prolog = &xml[..root.range().start];
+            if !value.is_object() {
+                value = Value::Object(Map::new());
+            }
+            if let Some(map) = value.as_object_mut() {
+                map.insert(
+                    "#declaration".into(),
+                    declaration::declaration_value(prolog),
+                );
+            }
+        }
+        if self.strict {
+            if let Some(loss) = ctx.report.losses.first() {
+                return Err(Box::new(loss.clone()));
+            }
+        }
+        Ok(self.flatten_output(self.rename_keys(value)))
+    }
+
+    pub(crate) fn parsing_options(&self) -> roxmltree::ParsingOptions {
+        roxmltree::ParsingOptions {
+            allow_dtd: self.allow_dtd,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn parse_root(
+        &self,
+        node: &roxmltree::Node,
+        ctx: &mut Context,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        let mut name = self.element_name(node);
+        let path_len = ctx.enter(&name);
+        if let ElementAction::Rename(rename) = self.element_action(node, ctx) {
+            name = Cow::Owned(rename);
+        }
+        let value = self.parse_node(node, ctx)?;
+        ctx.leave(path_len);
+        if self.with_root {
+            let mut root = Map::new();
+            root.insert(name.into_owned(), value.unwrap_or(Value::Null));
+            Ok(Some(Value::Object(root)))
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// record comments and processing instructions, which are never converted
+    fn check_ignored_node(&self, node: &roxmltree::Node, ctx: &mut Context) {
+        match node.node_type() {
+            roxmltree::NodeType::Comment => self.record_loss(ctx, LossKind::Comment),
+            roxmltree::NodeType::PI => {
+                let target = node.pi().map(|pi| pi.target).unwrap_or_default();
+                self.record_loss(ctx, LossKind::ProcessingInstruction(target.into()))
+            }
+            _ => {}
+        }
+    }
+
+    /// record information of `node` that gets lost by `parse_node`
+    fn check_losses(&self, node: &roxmltree::Node, ctx: &mut Context) {
+        let tag_name = node.tag_name();
+        if let Some(uri) = tag_name.namespace() {
+            if self.element_name(node) == tag_name.name() {
+                self.record_loss(ctx, LossKind::Namespace(uri.into()));
+            }
+        }
+        for attr in node.attributes() {
+            if let Some(uri) = attr.namespace() {
+                if self.namespaced_name(node, Some(uri), attr.name()) == attr.name() {
+                    self.record_loss(ctx, LossKind::Namespace(uri.into()));
+                }
+            }
+            if self.attribute_value(attr.value()) != attr.value() {
+                self.record_loss(ctx, LossKind::TrimmedWhitespace);
+            }
+        }
+        let mut seen: Vec<&str> = Vec::new();
+        for (pos, child) in node.children().enumerate() {
+            if child.is_text() {
+                let text = child.text().unwrap_or_default();
+                let trimmed = text.trim();
+                if pos > 0 && !trimmed.is_empty() {
+                    self.record_loss(ctx, LossKind::MixedContent(trimmed.into()));
+                } else if !trimmed.is_empty() && trimmed != text {
+                    self.record_loss(ctx, LossKind::TrimmedWhitespace);
+                }
+            } else if child.is_element() {
+                let name = child.tag_name().name();
+                if seen.last().is_some_and(|last| *last != name) && seen.contains(&name) {
+                    self.record_loss(ctx, LossKind::SiblingOrder(name.into()));
+                }
+                seen.push(name);
+            } else {
+                self.check_ignored_node(&child, ctx);
+            }
+        }
+    }
+
+    /// returns the attribute name including its namespace prefix, e.g. `xml:lang`
+    fn qualified_attribute_name(node: &roxmltree::Node, attr: &roxmltree::Attribute) -> String {
+        match attr.namespace().and_then(|uri| node.lookup_prefix(uri)) {
+            Some(prefix) => [prefix, ":", attr.name()].concat(),
+            None => attr.name().to_string(),
+        }
+    }
+
+    /// returns `name` with its namespace according to the namespace mode
+    fn namespaced_name<'a>(
+        &self,
+        node: &roxmltree::Node,
+        namespace: Option<&str>,
+        name: &'a str,
+    ) -> Cow<'a, str> {
+        match (self.namespace_mode, namespace) {
+            (NamespaceMode::Strip, _) | (_, None) => Cow::Borrowed(name),
+            (NamespaceMode::Prefix, Some(uri)) => match node.lookup_prefix(uri) {
+                Some(prefix) => Cow::Owned([prefix, ":", name].concat()),
+                None => Cow::Borrowed(name),
+            },
+            (NamespaceMode::Expanded, Some(uri)) => Cow::Owned(["{", uri, "}", name].concat()),
+        }
+    }
+
+    /// returns true if `uri` is one of the namespaces whose elements and attributes are dropped
+    fn is_stripped_namespace(&self, uri: Option<&str>) -> bool {
+        uri.is_some_and(|uri| {
+            self.stripped_namespaces
+                .iter()
+                .any(|stripped| stripped == uri)
+        })
+    }
+
+    /// returns the key of an element
+    pub(crate) fn element_name<'a>(&self, node: &roxmltree::Node<'a, '_>) -> Cow<'a, str> {
+        let tag_name = node.tag_name();
+        self.namespaced_name(node, tag_name.namespace(), tag_name.name())
+    }
+
+    /// returns the key of an attribute including the attribute prefix
+    pub(crate) fn attribute_key(
+        &self,
+        node: &roxmltree::Node,
+        attr: &roxmltree::Attribute,
+    ) -> String {
+        let name = self.namespaced_name(node, attr.namespace(), attr.name());
+        [self.attribute_prefix.as_str(), &name].concat()
+    }
+
+    /// returns the value of an attribute, with `AttributeNormalization::Raw` tabs and
+    /// line breaks are taken from the document instead of the normalized value
+    pub(crate) fn source_attribute_value<'a>(
+        &self,
+        node: &roxmltree::Node<'a, '_>,
+        attr: &roxmltree::Attribute<'a, '_>,
+    ) -> Cow<'a, str> {
+        let raw = &node.document().input_text()[attr.range_value()];
+        if self.attribute_normalization == AttributeNormalization::Spec
+            || !raw.contains(['\t', '\n', '\r'])
+        {
+            return Cow::Borrowed(attr.value());
+        }
+        unescape_attribute(raw).map_or(Cow::Borrowed(attr.value()), Cow::Owned)
+    }
+
+    fn parse_text(
+        &self,
+        node: &roxmltree::Node,
+        text: &str,
+        ctx: &Context,
+    ) -> Result<(&str, Value), Box<dyn std::error::Error>> {
+        let text = text.trim();
+        if let Some(placeholder) = self.large_text(&ctx.path, text) {
+            return Ok((self.text_name_for(node.tag_name().name()), placeholder));
+        }
+        if !self
+            .binary_elements
+            .iter()
+            .any(|name| name == node.tag_name().name())
+        {
+            let val = match self.directive_type(node) {
+                Ok(Some(json_type)) => json_type
+                    .convert(text, self.number_mode)
+                    .map_err(|err| format!("{err} at '{}'", ctx.path))?,
+                Ok(None) => self.typed_value(&ctx.path, text)?,
+                Err(err) => return Err(format!("{err} at '{}'", ctx.path).into()),
+            };
+            return Ok((self.text_name_for(node.tag_name().name()), val));
+        }
+        let (normalized, len) = self
+            .binary_mode
+            .normalize(text)
+            .map_err(|err| format!("{err} at '{}'", ctx.path))?;
+        if self.binary_length_only {
+            Ok(("#byte_length", Value::from(len)))
+        } else {
+            Ok((
+                self.text_name_for(node.tag_name().name()),
+                Value::String(normalized),
+            ))
+        }
+    }
+
+    /// the text of `node` that is converted
+    fn element_text<'a>(&self, node: &roxmltree::Node<'a, '_>) -> Option<Cow<'a, str>> {
+        if !self.canonicalize {
+            return node.text().map(Cow::Borrowed);
+        }
+        let text: String = node
+            .children()
+            .take_while(|child| child.is_text())
+            .filter_map(|child| child.text())
+            .collect();
+        (!text.trim().is_empty()).then_some(Cow::Owned(text))
+    }
+
+    /// the attributes of `node` that are converted
+    fn converted_attributes<'a, 'input>(
+        &'a self,
+        node: &roxmltree::Node<'a, 'input>,
+    ) -> impl Iterator<Item = roxmltree::Attribute<'a, 'input>> + 'a {
+        node.attributes().filter(|attr| {
+            !self.is_stripped_namespace(attr.namespace()) && !self.is_filtered(attr.name())
+        })
+    }
+
+    /// the namespaces that are declared by `node` and not by its parent
+    fn declared_namespaces<'a, 'input>(
+        &self,
+        node: &roxmltree::Node<'a, 'input>,
+    ) -> impl Iterator<Item = &'a roxmltree::Namespace<'input>> {
+        let parent = node.parent_element();
+        let parent_namespaces: Vec<_> = parent.iter().flat_map(|p| p.namespaces()).collect();
+        node.namespaces().filter(move |namespace| {
+            namespace.name() != Some("xml") && !parent_namespaces.contains(namespace)
+        })
+    }
+
+    /// the child elements of `node` that are converted
+    pub(crate) fn converted_children<'a, 'input>(
+        &'a self,
+        node: &roxmltree::Node<'a, 'input>,
+    ) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> + 'a {
+        node.children().filter(|child| {
+            child.is_element()
+                && !self.is_stripped_namespace(child.tag_name().namespace())
+                && !self.is_filtered(child.tag_name().name())
+        })
+    }
+
+    /// Converts the text, attributes and namespace declarations of `node`, which is the part
+    /// of `parse_node` that doesn't depend on the children. The path of `node` has to be
+    /// entered in `ctx`.
+    pub(crate) fn element_start(
+        &self,
+        node: &roxmltree::Node,
+        ctx: &mut Context,
+    ) -> Result<ElementStart<'_>, Box<dyn std::error::Error>> {
+        self.check_cancelled(ctx)?;
+        // documents are checked before parsing, nodes passed to `node_to_json` are not
+        if ctx.depth > self.max_nesting_depth {
+            let limit = self.max_nesting_depth;
+            return Err(format!(
+                "nesting depth exceeds the limit of {limit} at '{}'",
+                ctx.path
+            )
+            .into());
+        }
+        ctx.elements += 1;
+        if let Some(stats) = &mut ctx.stats {
+            stats.count_element(node, ctx.depth);
+        }
+        if self.max_attributes.is_some() || self.max_name_length.is_some() {
+            let names = node.attributes().map(|attr| attr.name());
+            let names = std::iter::once(node.tag_name().name()).chain(names);
+            self.validate_element(ctx, node.attributes().len(), names)?;
+        }
+        let mut elements = Map::new();
+        if self.is_nil(node) {
+            return Ok(ElementStart::Done(Value::Null));
+        }
+        if self.is_raw(ctx.depth) {
+            let raw = &node.document().input_text()[node.range()];
+            elements.insert("#raw".into(), Value::String(raw.into()));
+            self.record_warning(ctx, WarningKind::DepthLimit);
+            return Ok(ElementStart::Done(Value::Object(elements)));
+        }
+        if ctx.check_losses {
+            self.check_losses(node, ctx);
+        }
+        let mut text_key = None;
+        if let Some(text) = self.element_text(node) {
+            let (key, val) = self.parse_text(node, &text, ctx)?;
+            elements.insert(key.into(), val);
+            text_key = Some(key);
+        }
+        for attr in self.converted_attributes(node) {
+            let key = self.attribute_key(node, &attr);
+            let presence = self.presence_value(attr.name(), attr.value());
+            let val = if let Some(presence) = presence {
+                presence
+            } else if self.type_overrides.is_empty() && self.datetime_names.is_none() {
+                Value::String(
+                    self.attribute_value(&self.source_attribute_value(node, &attr))
+                        .into(),
+                )
+            } else {
+                self.typed_value(
+                    &[&ctx.path, "/", &key].concat(),
+                    self.attribute_value(&self.source_attribute_value(node, &attr)),
+                )?
+            };
+            if let Some(old_val) = elements.insert(key, val) {
+                if ctx.check_losses {
+                    let key = self.attribute_key(node, &attr);
+                    let old_val = old_val.as_str().unwrap_or_default().to_string();
+                    self.record_loss(ctx, LossKind::Collision(key, old_val));
+                }
+            }
+        }
+        if self.namespace_declarations != NamespaceDeclarations::Drop {
+            for namespace in self.declared_namespaces(node) {
+                self.insert_namespace_declaration(&mut elements, namespace.name(), namespace.uri());
+            }
+        }
+        if let Some(duplicates) = ctx.duplicate_attributes.remove(&node.range().start) {
+            for (name, values) in duplicates {
+                let make_array = self.duplicate_attributes == Some(DuplicateAttributes::MakeArray);
+                if let Some(attr) = node
+                    .attributes()
+                    .find(|attr| make_array && Self::qualified_attribute_name(node, attr) == name)
+                {
+                    let values = values
+                        .iter()
+                        .map(|val| Value::String(self.attribute_value(val).into()));
+                    elements.insert(self.attribute_key(node, &attr), values.collect());
+                }
+                self.record_warning(ctx, WarningKind::DuplicateAttribute(name));
+            }
+        }
+        Ok(ElementStart::Entries(elements, text_key))
+    }
+
+    /// returns true if `node` is converted to null because of `xsi:nil`
+    fn is_nil(&self, node: &roxmltree::Node) -> bool {
+        matches!(self.null_policy, NullPolicy::XsiNil | NullPolicy::Null)
+            && matches!(node.attribute((NS_XSI, "nil")), Some("true" | "1"))
+    }
+
+    /// returns true if elements at `depth` are kept as raw XML, see `with_max_convert_depth`
+    fn is_raw(&self, depth: usize) -> bool {
+        self.max_convert_depth.is_some_and(|max| depth > max + 1)
+    }
+
+    /// returns true if `element_start` finds any entries of `node` at `depth`, or converts it
+    /// without its children
+    fn has_entries(&self, node: &roxmltree::Node, depth: usize, ctx: &Context) -> bool {
+        self.is_nil(node)
+            || self.is_raw(depth)
+            || self.element_text(node).is_some()
+            || self.converted_attributes(node).next().is_some()
+            || (self.namespace_declarations != NamespaceDeclarations::Drop
+                && self.declared_namespaces(node).next().is_some())
+            || (self.duplicate_attributes == Some(DuplicateAttributes::MakeArray)
+                && ctx.duplicate_attributes.contains_key(&node.range().start))
+    }
+
+    pub(crate) fn parse_node(
+        &self,
+        node: &roxmltree::Node,
+        ctx: &mut Context,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        match self.element_start(node, ctx)? {
+            ElementStart::Done(value) => Ok(Some(value)),
+            ElementStart::Entries(elements, text_key) => {
+                self.parse_children(node, elements, text_key.is_some(), ctx)
+            }
+        }
+    }
+
+    /// add the children of `node` to the `elements` of `element_start`
+    fn parse_children(
+        &self,
+        node: &roxmltree::Node,
+        mut elements: Map<String, Value>,
+        has_text: bool,
+        ctx: &mut Context,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        let parent_inherited = if self.inherited_attributes.is_empty() {
+            None
+        } else {
+            Some(ctx.inherited.clone())
+        };
+        if parent_inherited.is_some() {
+            for attr in node.attributes() {
+                if self
+                    .inherited_attributes
+                    .contains(&Self::qualified_attribute_name(node, &attr))
+                {
+                    let key = self.attribute_key(node, &attr);
+                    ctx.inherited.insert(
+                        key,
+                        Value::String(
+                            self.attribute_value(&self.source_attribute_value(node, &attr))
+                                .into(),
+                        ),
+                    );
+                }
+            }
+        }
+        let attribute_count = elements.len();
+        if self.max_output_bytes.is_some() {
+            ctx.output_bytes += elements
+                .iter()
+                .map(|(key, val)| key.len() + val.as_str().map_or(8, str::len) + 6)
+                .sum::<usize>();
+        }
+        for (index, child) in self.converted_children(node).enumerate() {
+            let name = self.element_name(&child);
+            if self
+                .max_output_bytes
+                .is_some_and(|max| ctx.output_bytes > max)
+            {
+                elements.insert("#truncated".into(), Value::Bool(true));
+                self.record_warning(ctx, WarningKind::Truncated);
+                break;
+            }
+            if self.is_include(&child) {
+                if let (name, Some(mut child_val)) = self.parse_include(&child, ctx)? {
+                    self.set_sibling_index(&mut child_val, index);
+                    self.insert_child(&mut elements, &name, child_val, false, ctx)?;
+                }
+            } else {
+                let path_len = ctx.enter(&name);
+                let mut action = self.element_action(&child, ctx);
+                if action == ElementAction::Continue && self.array_paths.contains(&ctx.path) {
+                    action = ElementAction::ForceArray;
+                }
+                let child_val = match action {
+                    ElementAction::Skip => None,
+                    _ => self.parse_node(&child, ctx)?,
+                };
+                ctx.leave(path_len);
+                let array = action == ElementAction::ForceArray;
+                let name = match action {
+                    ElementAction::Rename(rename) => Cow::Owned(rename),
+                    _ => name,
+                };
+                if let Some(mut child_val) = child_val {
+                    self.set_sibling_index(&mut child_val, index);
+                    self.insert_child(&mut elements, &name, child_val, array, ctx)?;
+                    ctx.output_bytes += name.len() + 4;
+                }
+            }
+        }
+        self.promote_attributes(&mut elements);
+        self.deduplicate_siblings(&mut elements);
+        self.apply_array_directives(node, &mut elements);
+        if self.flatten_text_arrays || self.inline_simple_children.is_some() {
+            self.flatten_text_arrays(&mut elements);
+        }
+        if !has_text && attribute_count > 0 && elements.len() == attribute_count {
+            elements = self.compact_attributes(&self.element_name(node), elements);
+        }
+        let inherited = match parent_inherited {
+            Some(parent_inherited) => std::mem::replace(&mut ctx.inherited, parent_inherited),
+            None => Map::new(),
+        };
+        if self.preserve_attribute_order && node.attributes().len() > 1 {
+            let names: Vec<Value> = self
+                .converted_attributes(node)
+                .map(|attr| {
+                    let name = self.namespaced_name(node, attr.namespace(), attr.name());
+                    Value::String(name.into_owned())
+                })
+                .collect();
+            elements.insert("#attr_order".into(), Value::Array(names));
+        }
+        if elements.is_empty() {
+            match self.null_policy {
+                NullPolicy::Null => return Ok(Some(Value::Null)),
+                NullPolicy::Never => {}
+                NullPolicy::Skip | NullPolicy::XsiNil => return Ok(None),
+            }
+        }
+        for (key, val) in inherited {
+            elements.entry(key).or_insert(val);
+        }
+        if self.source_spans {
+            elements.insert("#span".into(), Self::source_span(node));
+        }
+        Ok(Some(Value::Object(elements)))
+    }
+
+    /// Convert `xml` and emit it into `sink` while traversing the document, without building
+    /// a `Value`. Only for the options of `streams_to_sink`.
+    pub(crate) fn emit_document<S: JsonSink>(
+        &self,
+        xml: &str,
+        sink: &mut S,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut ctx = Context {
+            check_losses: self.strict || self.loss_callback.is_some(),
+            ..Default::default()
+        };
+        self.traced(xml.len(), &mut ctx, |ctx| {
+            ctx.deadline = self.deadline_from_now();
+            let xml = self.preprocess(xml, ctx)?;
+            let doc = roxmltree::Document::parse_with_options(&xml, self.parsing_options())?;
+            let root = doc.root_element();
+            if ctx.check_losses {
+                for node in doc.root().children().filter(|node| *node != root) {
+                    self.check_ignored_node(&node, ctx);
+                }
+            }
+            let mut yields = vec![None; doc.descendants().count()];
+            let name = self.element_name(&root);
+            let path_len = ctx.enter(&name);
+            if self.with_root {
+                sink.begin_object(1);
+                sink.key(&name);
+            }
+            match self.yields_value(&root, ctx.depth, ctx, &mut yields) {
+                true => self.emit_node(&root, ctx, &mut yields, sink)?,
+                false => sink.null(),
+            }
+            if self.with_root {
+                sink.end_object();
+            }
+            ctx.leave(path_len);
+            if self.strict {
+                if let Some(loss) = ctx.report.losses.first() {
+                    return Err(Box::new(loss.clone()));
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// returns true if `parse_node` converts `node` at `depth` to a value, memoized by node id
+    fn yields_value(
+        &self,
+        node: &roxmltree::Node,
+        depth: usize,
+        ctx: &Context,
+        yields: &mut [Option<bool>],
+    ) -> bool {
+        if let Some(known) = yields[node.id().get_usize()] {
+            return known;
+        }
+        let known = !matches!(self.null_policy, NullPolicy::Skip | NullPolicy::XsiNil)
+            || self.has_entries(node, depth, ctx)
+            || self
+                .converted_children(node)
+                .any(|child| self.yields_value(&child, depth + 1, ctx, yields));
+        yields[node.id().get_usize()] = Some(known);
+        known
+    }
+
+    /// Emit the value of `node` into `sink`, the streaming counterpart of `parse_node`.
+    /// `node` has to yield a value.
+    fn emit_node<S: JsonSink>(
+        &self,
+        node: &roxmltree::Node,
+        ctx: &mut Context,
+        yields: &mut [Option<bool>],
+        sink: &mut S,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (entries, text_key) = match self.element_start(node, ctx)? {
+            ElementStart::Done(value) => {
+                emit_value(&value, sink);
+                return Ok(());
+            }
+            ElementStart::Entries(entries, text_key) => (entries, text_key),
+        };
+        // children with a value, grouped by their key in document order
+        let mut groups: Vec<(Cow<str>, bool, Vec<roxmltree::Node>)> = Vec::new();
+        let mut group_index: HashMap<Cow<str>, usize> = HashMap::new();
+        let mut collision = false;
+        for child in self.converted_children(node) {
+            if !self.yields_value(&child, ctx.depth + 1, ctx, yields) {
+                continue;
+            }
+            let name = self.element_name(&child);
+            let (key, plural) = match self
+                .plural_names
+                .iter()
+                .find(|(singular, _)| *singular == name)
+            {
+                Some((_, plural)) => (Cow::Borrowed(plural.as_str()), true),
+                None => (name, false),
+            };
+            match group_index.get(&key) {
+                Some(&index) => {
+                    collision |= groups[index].1 != plural;
+                    groups[index].2.push(child);
+                }
+                None => {
+                    collision |= entries.contains_key(key.as_ref());
+                    group_index.insert(key.clone(), groups.len());
+                    groups.push((key, plural, vec![child]));
+                }
+            }
+        }
+        if collision {
+            // children that share a key with an attribute, the text or a plural name are
+            // merged by `insert_child`
+            let value = self.parse_children(node, entries, text_key.is_some(), ctx)?;
+            emit_value(&value.unwrap_or(Value::Null), sink);
+            return Ok(());
+        }
+        if entries.is_empty() && groups.is_empty() && self.null_policy != NullPolicy::Never {
+            sink.null();
+            return Ok(());
+        }
+        sink.begin_object(entries.len() + groups.len() + usize::from(self.source_spans));
+        for (key, val) in &entries {
+            sink.key(key);
+            emit_value(val, sink);
+        }
+        for (key, plural, children) in groups {
+            sink.key(&key);
+            let first = self.element_name(&children[0]);
+            let path_len = ctx.enter(&first);
+            let forced = self.array_paths.contains(&ctx.path);
+            ctx.leave(path_len);
+            let repeated = !plural && !forced && children.len() > 1;
+            if repeated && self.array_mode == ArrayMode::ErrorOnUnexpectedRepeat {
+                return Err(
+                    format!("unexpected repeated element '{first}' at '{}'", ctx.path).into(),
+                );
+            }
+            if repeated && self.array_mode != ArrayMode::AlwaysWrapChildren {
+                let path = &ctx.path;
+                self.trace(TraceEvent::ArrayPromoted { path, name: &first });
+            }
+            let array =
+                plural || forced || repeated || self.array_mode == ArrayMode::AlwaysWrapChildren;
+            if array {
+                sink.begin_array(children.len());
+            }
+            for child in children {
+                let path_len = ctx.enter(&self.element_name(&child));
+                self.emit_node(&child, ctx, yields, sink)?;
+                ctx.leave(path_len);
+            }
+            if array {
+                sink.end_array();
+            }
+        }
+        if self.source_spans {
+            sink.key("#span");
+            emit_value(&Self::source_span(node), sink);
+        }
+        sink.end_object();
+        Ok(())
+    }
+
+    /// byte range and 1-based position of `node` in the parsed input
+    fn source_span(node: &roxmltree::Node) -> Value {
+        let range = node.range();
+        let pos = node.document().text_pos_at(range.start);
+        let mut span = Map::new();
+        span.insert("start".into(), range.start.into());
+        span.insert("end".into(), range.end.into());
+        span.insert("line".into(), pos.row.into());
+        span.insert("col".into(), pos.col.into());
+        Value::Object(span)
+    }
+}
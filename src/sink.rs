@@ -0,0 +1,252 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::{Map, Number, Value};
+
+/// Receives the converted document as a sequence of JSON events, see `XmlToJson::xml_to_sink`.
+/// Implement this to build other value types, like `simd_json::OwnedValue`, or to stream the
+/// output directly into a writer.
+pub trait JsonSink {
+    type Output;
+    fn null(&mut self);
+    fn bool(&mut self, val: bool);
+    fn number(&mut self, val: &Number);
+    fn string(&mut self, val: &str);
+    /// starts an object, followed by pairs of `key` and value events
+    fn begin_object(&mut self, len: usize);
+    fn key(&mut self, key: &str);
+    fn end_object(&mut self);
+    fn begin_array(&mut self, len: usize);
+    fn end_array(&mut self);
+    /// called once after the last event
+    fn finish(self) -> Self::Output;
+}
+
+/// Builds a `serde_json::Value`
+#[derive(Default)]
+pub struct ValueSink {
+    stack: Vec<(Value, Option<String>)>,
+    key: Option<String>,
+    result: Option<Value>,
+}
+
+impl ValueSink {
+    fn push_value(&mut self, val: Value) {
+        match self.stack.last_mut() {
+            Some((Value::Object(map), _)) => {
+                map.insert(self.key.take().unwrap_or_default(), val);
+            }
+            Some((Value::Array(array), _)) => array.push(val),
+            _ => self.result = Some(val),
+        }
+    }
+
+    fn end_container(&mut self) {
+        if let Some((val, key)) = self.stack.pop() {
+            self.key = key;
+            self.push_value(val);
+        }
+    }
+}
+
+impl JsonSink for ValueSink {
+    type Output = Value;
+    fn null(&mut self) {
+        self.push_value(Value::Null);
+    }
+    fn bool(&mut self, val: bool) {
+        self.push_value(Value::Bool(val));
+    }
+    fn number(&mut self, val: &Number) {
+        self.push_value(Value::Number(val.clone()));
+    }
+    fn string(&mut self, val: &str) {
+        self.push_value(Value::String(val.into()));
+    }
+    fn begin_object(&mut self, _len: usize) {
+        let key = self.key.take();
+        self.stack.push((Value::Object(Map::new()), key));
+    }
+    fn key(&mut self, key: &str) {
+        self.key = Some(key.into());
+    }
+    fn end_object(&mut self) {
+        self.end_container();
+    }
+    fn begin_array(&mut self, len: usize) {
+        let key = self.key.take();
+        self.stack
+            .push((Value::Array(Vec::with_capacity(len)), key));
+    }
+    fn end_array(&mut self) {
+        self.end_container();
+    }
+    fn finish(self) -> Value {
+        self.result.unwrap_or(Value::Null)
+    }
+}
+
+/// emit all events of `value` into `sink`
+pub fn emit_value<S: JsonSink>(value: &Value, sink: &mut S) {
+    match value {
+        Value::Null => sink.null(),
+        Value::Bool(val) => sink.bool(*val),
+        Value::Number(val) => sink.number(val),
+        Value::String(val) => sink.string(val),
+        Value::Array(array) => {
+            sink.begin_array(array.len());
+            array.iter().for_each(|val| emit_value(val, sink));
+            sink.end_array();
+        }
+        Value::Object(map) => {
+            sink.begin_object(map.len());
+            for (key, val) in map {
+                sink.key(key);
+                emit_value(val, sink);
+            }
+            sink.end_object();
+        }
+    }
+}
+
+impl XmlToJson {
+    /// Parse XML string and feed the result into `sink` while traversing the document,
+    /// without building a `Value`. Options that rewrite the converted objects need them
+    /// complete, so with key renames, transforms, templates, flattening, promoted, inherited
+    /// or compacted attributes, sibling indexes or deduplication, element callbacks,
+    /// directives, includes, output limits, declarations or case insensitive keys, and with
+    /// the quick-xml backend, the document is converted to a `Value` first.
+    pub fn xml_to_sink<S: JsonSink>(
+        &self,
+        xml: &str,
+        mut sink: S,
+    ) -> Result<S::Output, Box<dyn std::error::Error>> {
+        #[cfg(feature = "roxmltree")]
+        if self.streams_to_sink() {
+            self.emit_document(xml, &mut sink)?;
+            return Ok(sink.finish());
+        }
+        let value = self.xml_to_json(xml)?;
+        emit_value(&value, &mut sink);
+        Ok(sink.finish())
+    }
+
+    /// returns true if no option needs the complete converted objects, see `xml_to_sink`
+    #[cfg(feature = "roxmltree")]
+    pub(crate) fn streams_to_sink(&self) -> bool {
+        self.key_renames.is_empty()
+            && self.transforms.is_empty()
+            && self.output_template.is_empty()
+            && self.flatten_separator.is_none()
+            && !self.flatten_text_arrays
+            && self.inline_simple_children.is_none()
+            && self.promoted_attributes.is_empty()
+            && self.inherited_attributes.is_empty()
+            && self.compact_attribute_elements.is_none()
+            && !self.sibling_index
+            && self.sibling_deduplication.is_none()
+            && self.element_callback.is_none()
+            && !self.directives
+            && self.include_resolver.is_none()
+            && self.max_output_bytes.is_none()
+            && !self.with_declaration
+            && !self.case_insensitive_keys
+            && !self.preserve_attribute_order
+    }
+}
+
+#[test]
+fn test_xml_to_sink() {
+    struct CountingSink(usize);
+    impl JsonSink for CountingSink {
+        type Output = usize;
+        fn null(&mut self) {}
+        fn bool(&mut self, _: bool) {}
+        fn number(&mut self, _: &Number) {}
+        fn string(&mut self, _: &str) {
+            self.0 += 1;
+        }
+        fn begin_object(&mut self, _: usize) {}
+        fn key(&mut self, _: &str) {}
+        fn end_object(&mut self) {}
+        fn begin_array(&mut self, _: usize) {}
+        fn end_array(&mut self) {}
+        fn finish(self) -> usize {
+            self.0
+        }
+    }
+
+    let xml =
+        "<a><b href=\"#self\">simple</b><b><c class=\"my_class\"><d>D</d><d>1</d></c></b></a>";
+    let parser = XmlToJson::default();
+    assert_eq!(
+        parser.xml_to_sink(xml, ValueSink::default()).unwrap(),
+        parser.xml_to_json(xml).unwrap()
+    );
+    assert_eq!(parser.xml_to_sink(xml, CountingSink(0)).unwrap(), 5);
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_xml_to_sink_streams() {
+    use crate::{
+        ArrayMode, DuplicateAttributes, FilterAction, JsonType, NamespaceDeclarations,
+        NamespaceMode, NullPolicy,
+    };
+    use std::collections::HashMap;
+
+    let docs = [
+        "<a id=\"1\"><b>x</b><c/><b y=\"2\"/><d><b>1</b></d>tail<b/></a>",
+        "<a xmlns=\"urn:a\" xmlns:n=\"urn:n\"><n:b n:x=\"1\">t</n:b><c xmlns:m=\"urn:m\"/></a>",
+        "<a xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"><b xsi:nil=\"true\"/><c/></a>",
+        "<a><item>1</item><item>2</item><e><f><g>deep</g></f></e><secret/><x x=\"1\" x=\"2\"/></a>",
+        "<a>\n  <b/>\n  <c>\n  </c>\n</a>",
+        "<a x=\"1\"><x>child</x><value>v</value>text</a>",
+        "<a/>",
+    ];
+    let mut overrides = HashMap::new();
+    overrides.insert("/a/b".to_string(), JsonType::Number);
+    overrides.insert("/a/item/#text".to_string(), JsonType::Number);
+    let parsers = [
+        XmlToJson::default(),
+        XmlToJson::default()
+            .with_root()
+            .with_null_policy(NullPolicy::Null)
+            .with_plural_names(&[("item", "items"), ("c", "b")]),
+        XmlToJson::default()
+            .with_null_policy(NullPolicy::Never)
+            .with_array_mode(ArrayMode::AlwaysWrapChildren)
+            .with_namespace_mode(NamespaceMode::Prefix)
+            .with_namespace_declarations(NamespaceDeclarations::Keep),
+        XmlToJson::default()
+            .with_null_policy(NullPolicy::XsiNil)
+            .with_array_paths(&["/a/d/b", "/a/e"])
+            .with_namespace_mode(NamespaceMode::Expanded)
+            .with_namespace_declarations(NamespaceDeclarations::Collect)
+            .with_key_filter("secret", FilterAction::Drop)
+            .with_strip_namespaces(&["urn:m"]),
+        XmlToJson::default()
+            .with_attribute_prefix("")
+            .with_text_name("value")
+            .with_type_overrides(overrides)
+            .with_max_convert_depth(1)
+            .with_duplicate_attributes(DuplicateAttributes::MakeArray)
+            .with_source_spans()
+            .with_canonicalization(),
+    ];
+    for parser in &parsers {
+        assert!(parser.streams_to_sink());
+        for doc in docs {
+            assert_eq!(
+                parser.xml_to_sink(doc, ValueSink::default()).ok(),
+                parser.xml_to_json(doc).ok(),
+                "{doc}"
+            );
+        }
+    }
+    let parser = XmlToJson::default().with_array_mode(ArrayMode::ErrorOnUnexpectedRepeat);
+    assert!(parser.xml_to_sink(docs[0], ValueSink::default()).is_err());
+}
@@ -0,0 +1,85 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{JsonType, NamespaceMode, XmlToJson};
+use std::collections::HashMap;
+
+/// The naming and typing rules that are shared by the conversion to JSON and the conversion
+/// back to XML with `XmlToJson::json_to_xml_pretty`, so both directions stay consistent
+#[derive(Clone, Debug, PartialEq)]
+pub struct MappingSpec {
+    /// key of element texts, default is `#text`
+    pub text_name: String,
+    /// prefix of attribute keys, default is `@`
+    pub attribute_prefix: String,
+    /// text names of single elements, see `XmlToJson::with_text_name_for`
+    pub text_names: Vec<(String, String)>,
+    /// singular element names and the plural keys of their arrays
+    pub plural_names: Vec<(String, String)>,
+    pub namespace_mode: NamespaceMode,
+    /// JSON types by XML path, see `XmlToJson::with_type_overrides`
+    pub type_overrides: HashMap<String, JsonType>,
+}
+
+impl Default for MappingSpec {
+    fn default() -> Self {
+        XmlToJson::default().mapping_spec()
+    }
+}
+
+impl XmlToJson {
+    /// use the naming and typing rules of `spec`
+    pub fn with_mapping_spec(self, spec: &MappingSpec) -> Self {
+        let spec = spec.clone();
+        XmlToJson {
+            text_name: spec.text_name,
+            attribute_prefix: spec.attribute_prefix,
+            text_names: spec.text_names,
+            plural_names: spec.plural_names,
+            namespace_mode: spec.namespace_mode,
+            type_overrides: spec.type_overrides,
+            ..self
+        }
+    }
+
+    /// returns the naming and typing rules of this converter
+    pub fn mapping_spec(&self) -> MappingSpec {
+        MappingSpec {
+            text_name: self.text_name.clone(),
+            attribute_prefix: self.attribute_prefix.clone(),
+            text_names: self.text_names.clone(),
+            plural_names: self.plural_names.clone(),
+            namespace_mode: self.namespace_mode,
+            type_overrides: self.type_overrides.clone(),
+        }
+    }
+
+    /// the element name of the JSON key `key`, reverting plural names
+    pub(crate) fn singular_name<'a>(&'a self, key: &'a str) -> &'a str {
+        self.plural_names
+            .iter()
+            .find(|(_, plural)| plural == key)
+            .map_or(key, |(singular, _)| singular)
+    }
+}
+
+#[test]
+fn test_mapping_spec() {
+    let spec = MappingSpec {
+        text_name: "value".into(),
+        attribute_prefix: "_".into(),
+        plural_names: vec![("item".into(), "items".into())],
+        ..Default::default()
+    };
+    let parser = XmlToJson::default().with_mapping_spec(&spec);
+    let value = parser
+        .xml_to_json("<list kind=\"a\"><item>1</item><item id=\"2\">2</item></list>")
+        .unwrap();
+    assert_eq!(
+        parser.json_to_xml_pretty(&value, "list", 2).unwrap(),
+        "<list kind=\"a\">\n  <item>1</item>\n  <item id=\"2\">2</item>\n</list>\n"
+    );
+    assert_eq!(parser.mapping_spec(), spec);
+}
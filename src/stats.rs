@@ -0,0 +1,94 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+#[cfg(feature = "roxmltree")]
+use crate::{Context, XmlToJson};
+use serde_json::Value;
+use std::time::Duration;
+
+/// Numbers about a single conversion, see `XmlToJson::convert_with_stats`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConversionStats {
+    /// number of converted elements, including the root
+    pub elements: usize,
+    /// number of attributes of converted elements
+    pub attributes: usize,
+    /// number of text nodes that are not only whitespace
+    pub text_nodes: usize,
+    /// deepest nesting level of converted elements, the root element has level 1
+    pub max_depth: usize,
+    /// number of arrays in the output
+    pub arrays_created: usize,
+    /// length of the input in bytes
+    pub bytes_in: usize,
+    /// time spent for parsing and conversion
+    pub duration: Duration,
+}
+
+impl ConversionStats {
+    #[cfg(feature = "roxmltree")]
+    pub(crate) fn count_element(&mut self, node: &roxmltree::Node, depth: usize) {
+        self.elements += 1;
+        self.attributes += node.attributes().len();
+        self.text_nodes += node
+            .children()
+            .filter(|child| {
+                child.is_text() && child.text().is_some_and(|text| !text.trim().is_empty())
+            })
+            .count();
+        self.max_depth = self.max_depth.max(depth);
+    }
+}
+
+/// count all arrays in `val`, including nested ones
+fn count_arrays(val: &Value) -> usize {
+    match val {
+        Value::Array(array) => 1 + array.iter().map(count_arrays).sum::<usize>(),
+        Value::Object(map) => map.values().map(count_arrays).sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+impl XmlToJson {
+    /// Parse XML string and return serde_json Value together with statistics about the
+    /// conversion, for capacity planning and monitoring
+    pub fn convert_with_stats(
+        &self,
+        xml: &str,
+    ) -> Result<(Value, ConversionStats), Box<dyn std::error::Error>> {
+        let start = std::time::Instant::now();
+        let mut ctx = Context {
+            check_losses: self.strict || self.loss_callback.is_some(),
+            stats: Some(ConversionStats::default()),
+            ..Default::default()
+        };
+        let value = self.convert_with_context(xml, &mut ctx)?;
+        let mut stats = ctx.stats.unwrap_or_default();
+        stats.arrays_created = count_arrays(&value);
+        stats.bytes_in = xml.len();
+        stats.duration = start.elapsed();
+        Ok((value, stats))
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_convert_with_stats() {
+    let xml = r#"<a x="1"><b>1</b><b y="2" z="3">2</b><c><d/></c></a>"#;
+    let (_, stats) = XmlToJson::default().convert_with_stats(xml).unwrap();
+    assert_eq!(
+        stats,
+        ConversionStats {
+            elements: 5,
+            attributes: 3,
+            text_nodes: 2,
+            max_depth: 3,
+            arrays_created: 1,
+            bytes_in: xml.len(),
+            duration: stats.duration,
+        }
+    );
+}
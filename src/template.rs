@@ -0,0 +1,121 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::{Map, Value};
+
+/// insert `val` at the dotted `output_path`, a trailing `[]` appends to an array
+fn insert_at(output: &mut Map<String, Value>, output_path: &str, val: Value) {
+    let (parents, last) = match output_path.rsplit_once('.') {
+        Some((parents, last)) => (Some(parents), last),
+        None => (None, output_path),
+    };
+    let mut target = output;
+    for segment in parents.into_iter().flat_map(|parents| parents.split('.')) {
+        let entry = target
+            .entry(segment)
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !entry.is_object() {
+            *entry = Value::Object(Map::new());
+        }
+        target = entry.as_object_mut().expect("replaced by an object");
+    }
+    match last.strip_suffix("[]") {
+        Some(key) => match target
+            .entry(key)
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            Value::Array(array) => array.push(val),
+            other => *other = Value::Array(vec![other.take(), val]),
+        },
+        None => {
+            target.insert(last.into(), val);
+        }
+    }
+}
+
+impl XmlToJson {
+    /// Produce the output from a template instead of the generic structure. Each entry maps
+    /// an XML path like `/order/@id` or `/order/items/item` to a dotted output path like
+    /// `order_id` or `customer.name`. Output paths ending with `[]` collect all matches into
+    /// an array. Elements that only contain text are mapped to their text.
+    pub fn with_output_template(mut self, template: &[(&str, &str)]) -> Self {
+        self.output_template = template
+            .iter()
+            .map(|(xml_path, output_path)| (xml_path.to_string(), output_path.to_string()))
+            .collect();
+        self
+    }
+
+    /// replace the converted `value` of the root element `root_name` by the template output
+    pub(crate) fn apply_template(&self, root_name: &str, value: Value) -> Value {
+        if self.output_template.is_empty() {
+            return value;
+        }
+        let root = match self.with_root {
+            true => value.get(root_name).unwrap_or(&Value::Null),
+            false => &value,
+        };
+        let mut output = Map::new();
+        self.collect_template(&mut format!("/{root_name}"), root, &mut output);
+        Value::Object(output)
+    }
+
+    fn collect_template(&self, path: &mut String, value: &Value, output: &mut Map<String, Value>) {
+        for (xml_path, output_path) in &self.output_template {
+            if xml_path == path {
+                let text = value
+                    .as_object()
+                    .filter(|map| map.len() == 1)
+                    .and_then(|map| {
+                        map.get(self.text_name_for(path.rsplit('/').next().unwrap_or_default()))
+                    });
+                insert_at(output, output_path, text.unwrap_or(value).clone());
+            }
+        }
+        let Value::Object(map) = value else {
+            return;
+        };
+        for (key, val) in map {
+            let len = path.len();
+            path.push('/');
+            path.push_str(key);
+            match val {
+                Value::Array(array) => array
+                    .iter()
+                    .for_each(|item| self.collect_template(path, item, output)),
+                _ => self.collect_template(path, val, output),
+            }
+            path.truncate(len);
+        }
+    }
+}
+
+#[test]
+fn test_output_template() {
+    use serde_json::json;
+
+    let xml = r#"<order id="7"><customer><name>Ann</name></customer>
+        <items><item sku="a">2</item><item sku="b">1</item></items></order>"#;
+    let parser = XmlToJson::default().with_output_template(&[
+        ("/order/@id", "order_id"),
+        ("/order/customer/name", "customer.name"),
+        ("/order/items/item", "items[]"),
+        ("/order/items/item/@sku", "skus[]"),
+    ]);
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({
+            "order_id": "7",
+            "customer": { "name": "Ann" },
+            "items": [{ "@sku": "a", "#text": "2" }, { "@sku": "b", "#text": "1" }],
+            "skus": ["a", "b"]
+        })
+    );
+    assert_eq!(
+        parser.with_root().xml_to_json(xml).unwrap()["order_id"],
+        json!("7")
+    );
+}
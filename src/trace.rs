@@ -0,0 +1,202 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{Context, Warning, XmlToJson};
+use std::time::{Duration, Instant};
+
+/// An event during a conversion, passed to the callback of `XmlToJson::with_trace`
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceEvent<'a> {
+    /// the conversion of a document with `bytes` bytes started
+    Started { bytes: usize },
+    /// a repeated child element `name` of `path` was converted into an array
+    ArrayPromoted { path: &'a str, name: &'a str },
+    /// a warning was recorded, also if no report was requested
+    Warning(&'a Warning),
+    /// the conversion of `elements` elements succeeded
+    Finished { elements: usize, duration: Duration },
+    /// the conversion failed
+    Failed { error: &'a str, duration: Duration },
+}
+
+#[cfg(feature = "tracing")]
+impl TraceEvent<'_> {
+    /// emit the event to `tracing`, inside of the span of the document
+    fn emit(&self) {
+        match self {
+            TraceEvent::Started { bytes } => tracing::debug!(bytes, "conversion started"),
+            TraceEvent::ArrayPromoted { path, name } => {
+                tracing::trace!(path, name, "repeated element converted into an array")
+            }
+            TraceEvent::Warning(warning) => {
+                tracing::warn!(path = warning.path, "{}", warning.kind)
+            }
+            TraceEvent::Finished { elements, duration } => {
+                tracing::debug!(elements, ?duration, "conversion finished")
+            }
+            TraceEvent::Failed { error, duration } => {
+                tracing::warn!(error, ?duration, "conversion failed")
+            }
+        }
+    }
+}
+
+impl XmlToJson {
+    /// call `callback` for each `TraceEvent` of `xml_to_json`, e.g. to forward them to
+    /// `log`. Document conversions of both backends are traced. With the feature `tracing`,
+    /// each document is converted in a `xml_to_json` span and the events, losses and limit
+    /// errors are also emitted as `tracing` events.
+    pub fn with_trace(mut self, callback: impl Fn(&TraceEvent) + Send + Sync + 'static) -> Self {
+        self.trace = Some(Box::new(callback));
+        self
+    }
+
+    pub(crate) fn trace(&self, event: TraceEvent) {
+        #[cfg(feature = "tracing")]
+        event.emit();
+        if let Some(callback) = &self.trace {
+            callback(&event);
+        }
+    }
+
+    /// run `convert` between a started and a finished or failed event
+    pub(crate) fn traced<T>(
+        &self,
+        bytes: usize,
+        ctx: &mut Context,
+        convert: impl FnOnce(&mut Context) -> Result<T, Box<dyn std::error::Error>>,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        if self.trace.is_none() && !cfg!(feature = "tracing") {
+            return convert(ctx);
+        }
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!("xml_to_json", bytes, elements = tracing::field::Empty);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        let started = Instant::now();
+        self.trace(TraceEvent::Started { bytes });
+        let result = convert(ctx);
+        let duration = started.elapsed();
+        #[cfg(feature = "tracing")]
+        span.record("elements", ctx.elements);
+        match &result {
+            Ok(_) => self.trace(TraceEvent::Finished {
+                elements: ctx.elements,
+                duration,
+            }),
+            Err(err) => self.trace(TraceEvent::Failed {
+                error: &err.to_string(),
+                duration,
+            }),
+        }
+        result
+    }
+}
+
+#[test]
+fn test_trace() {
+    use std::sync::{Arc, Mutex};
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let parser = XmlToJson::default().with_trace(move |event| {
+        let event = match event {
+            TraceEvent::Finished { elements, .. } => format!("finished {elements}"),
+            TraceEvent::Failed { error, .. } => format!("failed {}", !error.is_empty()),
+            other => format!("{other:?}"),
+        };
+        recorded.lock().unwrap().push(event);
+    });
+    parser.xml_to_json("<a><b>1</b><b>2</b><c/></a>").unwrap();
+    parser.xml_to_json("<a>").unwrap_err();
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            "Started { bytes: 27 }",
+            "ArrayPromoted { path: \"/a\", name: \"b\" }",
+            "finished 4",
+            "Started { bytes: 3 }",
+            "failed true",
+        ]
+    );
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn test_tracing() {
+    use std::fmt::Write;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+
+    /// writes the fields of spans and events as `name: field=value …`
+    #[derive(Default)]
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() != "duration" {
+                write!(self.0, " {}={value:?}", field.name()).unwrap();
+            }
+        }
+    }
+
+    /// records spans and events
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl tracing::Subscriber for Recorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields::default();
+            span.record(&mut fields);
+            let name = span.metadata().name();
+            self.0.lock().unwrap().push(format!("{name}:{}", fields.0));
+            Id::from_u64(1)
+        }
+        fn record(&self, _: &Id, values: &Record<'_>) {
+            let mut fields = Fields::default();
+            values.record(&mut fields);
+            self.0.lock().unwrap().push(format!("record:{}", fields.0));
+        }
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            let level = event.metadata().level();
+            self.0.lock().unwrap().push(format!("{level}:{}", fields.0));
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    tracing::subscriber::with_default(Recorder(events.clone()), || {
+        let parser = XmlToJson::default().with_loss_callback(|_| {});
+        parser
+            .xml_to_json("<a><b>1</b><b>2</b><!-- c --></a>")
+            .unwrap();
+        XmlToJson::default()
+            .with_max_nesting_depth(1)
+            .xml_to_json("<a><b/></a>")
+            .unwrap_err();
+    });
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            "xml_to_json: bytes=33",
+            "DEBUG: message=conversion started bytes=33",
+            "DEBUG: message=comment ignored path=\"/a\"",
+            "TRACE: message=repeated element converted into an array path=\"/a\" name=\"b\"",
+            "record: elements=3",
+            "DEBUG: message=conversion finished elements=3",
+            "xml_to_json: bytes=11",
+            "DEBUG: message=conversion started bytes=11",
+            "record: elements=1",
+            "WARN: message=conversion failed error=\"nesting depth exceeds the limit of 1 at '/a/b'\"",
+        ]
+    );
+}
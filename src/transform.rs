@@ -0,0 +1,180 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::{KeyRename, XmlToJson};
+use serde_json::{Map, Value};
+
+/// A post-processing step for the converted JSON, see `XmlToJson::with_transform`.
+/// Implemented for closures, `KeyRename` and the built-in transforms of this module.
+pub trait Transform: Send + Sync {
+    fn apply(&self, value: Value) -> Value;
+}
+
+impl<F: Fn(Value) -> Value + Send + Sync> Transform for F {
+    fn apply(&self, value: Value) -> Value {
+        self(value)
+    }
+}
+
+impl Transform for KeyRename {
+    fn apply(&self, value: Value) -> Value {
+        XmlToJson::apply_renames(value, std::slice::from_ref(self), &mut String::new())
+    }
+}
+
+/// apply `f` to every object, children first
+fn map_objects(value: Value, f: &impl Fn(Map<String, Value>) -> Map<String, Value>) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(f(map
+            .into_iter()
+            .map(|(key, val)| (key, map_objects(val, f)))
+            .collect())),
+        Value::Array(array) => array.into_iter().map(|val| map_objects(val, f)).collect(),
+        other => other,
+    }
+}
+
+/// rename every key `from` to `to`
+pub struct RenameKey {
+    from: String,
+    to: String,
+}
+
+impl RenameKey {
+    pub fn new(from: &str, to: &str) -> Self {
+        RenameKey {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+}
+
+impl Transform for RenameKey {
+    fn apply(&self, value: Value) -> Value {
+        map_objects(value, &|map| {
+            map.into_iter()
+                .map(|(key, val)| match key == self.from {
+                    true => (self.to.clone(), val),
+                    false => (key, val),
+                })
+                .collect()
+        })
+    }
+}
+
+/// remove a prefix from all keys that start with it, e.g. the attribute prefix `@`
+pub struct StripPrefix(String);
+
+impl StripPrefix {
+    pub fn new(prefix: &str) -> Self {
+        StripPrefix(prefix.into())
+    }
+}
+
+impl Transform for StripPrefix {
+    fn apply(&self, value: Value) -> Value {
+        map_objects(value, &|map| {
+            map.into_iter()
+                .map(|(key, val)| match key.strip_prefix(self.0.as_str()) {
+                    Some(stripped) => (stripped.to_string(), val),
+                    None => (key, val),
+                })
+                .collect()
+        })
+    }
+}
+
+/// remove every key `key` including its value
+pub struct RemoveKey(String);
+
+impl RemoveKey {
+    pub fn new(key: &str) -> Self {
+        RemoveKey(key.into())
+    }
+}
+
+impl Transform for RemoveKey {
+    fn apply(&self, value: Value) -> Value {
+        map_objects(value, &|mut map| {
+            map.remove(&self.0);
+            map
+        })
+    }
+}
+
+/// replace every string, number, bool or null with the result of a function, which gets the
+/// key of the value and the value. Array items get the key of the array.
+pub struct MapValues<F>(F);
+
+impl<F: Fn(&str, Value) -> Value + Send + Sync> MapValues<F> {
+    pub fn new(f: F) -> Self {
+        MapValues(f)
+    }
+
+    fn map(&self, value: Value, key: &str) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, val)| {
+                        let val = self.map(val, &key);
+                        (key, val)
+                    })
+                    .collect(),
+            ),
+            Value::Array(array) => array.into_iter().map(|val| self.map(val, key)).collect(),
+            other => (self.0)(key, other),
+        }
+    }
+}
+
+impl<F: Fn(&str, Value) -> Value + Send + Sync> Transform for MapValues<F> {
+    fn apply(&self, value: Value) -> Value {
+        self.map(value, "")
+    }
+}
+
+/// sort the keys of all objects. Keys are already sorted, unless the `preserve_order`
+/// feature of serde_json is enabled.
+pub struct SortKeys;
+
+impl Transform for SortKeys {
+    fn apply(&self, value: Value) -> Value {
+        map_objects(value, &|map| {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            entries.into_iter().collect()
+        })
+    }
+}
+
+impl XmlToJson {
+    /// apply `transform` to the converted JSON, after the key renames and all previously
+    /// added transforms
+    pub fn with_transform(mut self, transform: impl Transform + 'static) -> Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+}
+
+#[test]
+fn test_transforms() {
+    use serde_json::json;
+
+    let xml = r#"<a id="1"><b>2</b><tmp>x</tmp><c lang="en">z</c></a>"#;
+    let parser = XmlToJson::default()
+        .with_transform(RemoveKey::new("tmp"))
+        .with_transform(StripPrefix::new("@"))
+        .with_transform(RenameKey::new("#text", "value"))
+        .with_transform(MapValues::new(|key, val| match (key, &val) {
+            ("value", Value::String(text)) => text.parse::<i64>().map_or(val, Value::from),
+            _ => val,
+        }))
+        .with_transform(SortKeys)
+        .with_transform(|val: Value| json!({ "doc": val }));
+    assert_eq!(
+        parser.xml_to_json(xml).unwrap(),
+        json!({ "doc": { "id": "1", "b": { "value": 2 }, "c": { "lang": "en", "value": "z" } } })
+    );
+}
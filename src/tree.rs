@@ -0,0 +1,185 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::writer::escape;
+use crate::XmlToJson;
+use serde_json::Value;
+
+/// An owned, mutable XML element, to modify documents between parsing and conversion
+/// without handling JSON values with `#text` and `@` keys.
+/// Names keep their namespace prefix, like `xlink:href`. Comments, processing instructions
+/// and the position of text between child elements are not kept.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct XmlNode {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+    pub text: Option<String>,
+}
+
+impl XmlNode {
+    pub fn new(name: &str) -> Self {
+        XmlNode {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Parse the root element of `xml`, which must not be nested deeper than the default
+    /// of `XmlToJson::with_max_nesting_depth`
+    #[cfg(feature = "roxmltree")]
+    pub fn parse(xml: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        crate::XmlToJson::default().check_nesting_depth(xml)?;
+        let doc = roxmltree::Document::parse(xml)?;
+        Ok(Self::from_roxmltree(&doc.root_element()))
+    }
+
+    #[cfg(feature = "roxmltree")]
+    fn from_roxmltree(node: &roxmltree::Node) -> Self {
+        let qualified = |namespace: Option<&str>, name: &str| match namespace
+            .and_then(|uri| node.lookup_prefix(uri))
+        {
+            Some(prefix) if !prefix.is_empty() => format!("{prefix}:{name}"),
+            _ => name.to_string(),
+        };
+        let tag_name = node.tag_name();
+        let parent_namespaces: Vec<_> = node
+            .parent_element()
+            .iter()
+            .flat_map(|parent| parent.namespaces())
+            .collect();
+        let declarations = node
+            .namespaces()
+            .filter(|namespace| {
+                namespace.name() != Some("xml") && !parent_namespaces.contains(namespace)
+            })
+            .map(|namespace| match namespace.name() {
+                Some(prefix) => (format!("xmlns:{prefix}"), namespace.uri().into()),
+                None => ("xmlns".into(), namespace.uri().into()),
+            });
+        let attributes = node.attributes().map(|attr| {
+            (
+                qualified(attr.namespace(), attr.name()),
+                attr.value().into(),
+            )
+        });
+        let text: String = node
+            .children()
+            .filter(|child| child.is_text())
+            .filter_map(|child| child.text())
+            .collect();
+        let text = text.trim();
+        XmlNode {
+            name: qualified(tag_name.namespace(), tag_name.name()),
+            attrs: declarations.chain(attributes).collect(),
+            children: node
+                .children()
+                .filter(|child| child.is_element())
+                .map(|child| Self::from_roxmltree(&child))
+                .collect(),
+            text: (!text.is_empty()).then(|| text.into()),
+        }
+    }
+
+    /// returns the value of attribute `name`
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(attr, _)| attr == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// set attribute `name`, replacing an existing value
+    pub fn set_attr(&mut self, name: &str, value: &str) {
+        match self.attrs.iter_mut().find(|(attr, _)| attr == name) {
+            Some((_, old)) => *old = value.into(),
+            None => self.attrs.push((name.into(), value.into())),
+        }
+    }
+
+    /// returns the first child element named `name`
+    pub fn child(&self, name: &str) -> Option<&XmlNode> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    pub fn child_mut(&mut self, name: &str) -> Option<&mut XmlNode> {
+        self.children.iter_mut().find(|child| child.name == name)
+    }
+
+    /// remove all child elements named `name`
+    pub fn remove_children(&mut self, name: &str) {
+        self.children.retain(|child| child.name != name);
+    }
+
+    /// Serialize as compact XML, the text is written in front of the children
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::new();
+        self.write_xml(&mut xml);
+        xml
+    }
+
+    fn write_xml(&self, xml: &mut String) {
+        xml.extend(["<", &self.name]);
+        for (name, value) in &self.attrs {
+            xml.extend([" ", name, "=\"", &escape(value, true), "\""]);
+        }
+        if self.text.is_none() && self.children.is_empty() {
+            xml.push_str("/>");
+            return;
+        }
+        xml.push('>');
+        if let Some(text) = &self.text {
+            xml.push_str(&escape(text, false));
+        }
+        for child in &self.children {
+            child.write_xml(xml);
+        }
+        xml.extend(["</", &self.name, ">"]);
+    }
+}
+
+impl XmlToJson {
+    /// Convert `node` with the rules of this converter, like `xml_to_json` of its XML
+    pub fn xml_node_to_json(&self, node: &XmlNode) -> Result<Value, Box<dyn std::error::Error>> {
+        self.xml_to_json(&node.to_xml())
+    }
+
+    /// Convert a JSON value back into an element named `name`, like `json_to_xml_pretty`
+    #[cfg(feature = "roxmltree")]
+    pub fn json_to_xml_node(
+        &self,
+        value: &Value,
+        name: &str,
+    ) -> Result<XmlNode, Box<dyn std::error::Error>> {
+        XmlNode::parse(&self.json_to_xml_pretty(value, name, 0)?)
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_xml_node() {
+    use serde_json::json;
+
+    let xml = r#"<a xmlns:n="urn:n"><b n:x="1">B &amp; C</b><debug/><c><d>D</d></c></a>"#;
+    let mut node = XmlNode::parse(xml).unwrap();
+    assert_eq!(node.child("b").unwrap().attr("n:x"), Some("1"));
+    node.remove_children("debug");
+    node.child_mut("c").unwrap().set_attr("id", "2");
+    let mut e = XmlNode::new("e");
+    e.text = Some("<E>".into());
+    node.children.push(e);
+    let parser = XmlToJson::default().with_namespace_mode(crate::NamespaceMode::Prefix);
+    let value = parser.xml_node_to_json(&node).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "b": { "@n:x": "1", "#text": "B & C" },
+            "c": { "@id": "2", "d": { "#text": "D" } },
+            "e": { "#text": "<E>" }
+        })
+    );
+    let c = parser.json_to_xml_node(&value["c"], "c").unwrap();
+    assert_eq!(Some(&c), node.child("c"));
+}
@@ -0,0 +1,141 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use serde_json::{Number, Value};
+
+/// Target JSON type of a text or attribute value, see `XmlToJson::with_type_overrides`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JsonType {
+    String,
+    Number,
+    /// `true`, `false`, `1` or `0`
+    Bool,
+}
+
+/// Defines how numbers that exceed the precision of `i64`, `u64` or `f64` are converted,
+/// see `XmlToJson::with_number_mode`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NumberMode {
+    /// keep numbers that can't be represented exactly as string
+    String,
+    /// round to the nearest `f64`
+    #[default]
+    Lossy,
+    /// keep all digits, requires the `arbitrary-precision` feature
+    #[cfg(feature = "arbitrary-precision")]
+    ArbitraryPrecision,
+}
+
+impl JsonType {
+    /// convert `text` into this type
+    pub(crate) fn convert(&self, text: &str, mode: NumberMode) -> Result<Value, String> {
+        match self {
+            JsonType::String => Ok(Value::String(text.into())),
+            JsonType::Number => {
+                parse_number(text, mode).ok_or_else(|| format!("'{text}' is not a number"))
+            }
+            JsonType::Bool => match text {
+                "true" | "1" => Ok(Value::Bool(true)),
+                "false" | "0" => Ok(Value::Bool(false)),
+                _ => Err(format!("'{text}' is not a boolean")),
+            },
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "string" => Some(JsonType::String),
+            "number" => Some(JsonType::Number),
+            "bool" => Some(JsonType::Bool),
+            _ => None,
+        }
+    }
+}
+
+fn parse_number(text: &str, mode: NumberMode) -> Option<Value> {
+    if let Ok(num) = text.parse::<i64>() {
+        return Some(num.into());
+    } else if let Ok(num) = text.parse::<u64>() {
+        return Some(num.into());
+    }
+    match mode {
+        NumberMode::Lossy => Number::from_f64(text.parse().ok()?).map(Value::Number),
+        NumberMode::String => {
+            let num = Number::from_f64(text.parse().ok()?)?;
+            // the shortest representation of the f64 differs if digits were lost
+            let trimmed = match text.contains('.') && !text.contains(['e', 'E']) {
+                true => text.trim_end_matches('0').trim_end_matches('.'),
+                false => text,
+            };
+            if num.to_string() == trimmed {
+                Some(Value::Number(num))
+            } else {
+                Some(Value::String(text.into()))
+            }
+        }
+        #[cfg(feature = "arbitrary-precision")]
+        NumberMode::ArbitraryPrecision => text.parse::<Number>().ok().map(Value::Number),
+    }
+}
+
+#[test]
+fn test_json_type_convert() {
+    use serde_json::json;
+
+    assert_eq!(
+        JsonType::Number.convert("42", NumberMode::Lossy).unwrap(),
+        json!(42)
+    );
+    assert_eq!(
+        JsonType::Number.convert("-1.5", NumberMode::Lossy).unwrap(),
+        json!(-1.5)
+    );
+    assert_eq!(
+        JsonType::Bool.convert("0", NumberMode::Lossy).unwrap(),
+        json!(false)
+    );
+    assert_eq!(
+        JsonType::String.convert("42", NumberMode::Lossy).unwrap(),
+        json!("42")
+    );
+    assert!(JsonType::Number.convert("NaN", NumberMode::Lossy).is_err());
+    assert!(JsonType::Bool.convert("yes", NumberMode::Lossy).is_err());
+}
+
+#[test]
+fn test_number_mode() {
+    use serde_json::json;
+
+    let big = "123456789012345678901234";
+    let decimal = "0.1000000000000000055";
+    assert_eq!(
+        JsonType::Number.convert(big, NumberMode::Lossy).unwrap(),
+        json!(1.2345678901234568e23)
+    );
+    assert_eq!(
+        JsonType::Number.convert(big, NumberMode::String).unwrap(),
+        json!(big)
+    );
+    assert_eq!(
+        JsonType::Number
+            .convert(decimal, NumberMode::String)
+            .unwrap(),
+        json!(decimal)
+    );
+    assert_eq!(
+        JsonType::Number
+            .convert("1.50", NumberMode::String)
+            .unwrap(),
+        json!(1.5)
+    );
+    #[cfg(feature = "arbitrary-precision")]
+    assert_eq!(
+        JsonType::Number
+            .convert(decimal, NumberMode::ArbitraryPrecision)
+            .unwrap()
+            .to_string(),
+        decimal
+    );
+}
@@ -0,0 +1,194 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::roxml::ElementStart;
+use crate::{Context, ElementAction, XmlToJson};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+
+/// Receives the elements of a document in depth-first order, see `XmlToJson::visit`.
+/// Names and keys follow the configuration of the converter, e.g. `@href` for attributes.
+pub trait Visitor {
+    /// `path` is the XML path of the element, like `/a/b`
+    fn start_element(&mut self, path: &str, name: &str);
+    fn attribute(&mut self, key: &str, value: &str);
+    fn text(&mut self, key: &str, text: &str);
+    fn end_element(&mut self, name: &str);
+}
+
+/// Builds the same JSON as `XmlToJson::xml_to_json` with the default options
+#[derive(Default)]
+pub struct JsonVisitor {
+    stack: Vec<Map<String, Value>>,
+    result: Option<Value>,
+}
+
+impl JsonVisitor {
+    /// returns the converted value of the root element
+    pub fn into_value(self) -> Value {
+        self.result.unwrap_or(Value::Null)
+    }
+}
+
+impl Visitor for JsonVisitor {
+    fn start_element(&mut self, _path: &str, _name: &str) {
+        self.stack.push(Map::new());
+    }
+
+    fn attribute(&mut self, key: &str, value: &str) {
+        if let Some(elements) = self.stack.last_mut() {
+            elements.insert(key.into(), Value::String(value.into()));
+        }
+    }
+
+    fn text(&mut self, key: &str, text: &str) {
+        self.attribute(key, text);
+    }
+
+    fn end_element(&mut self, name: &str) {
+        let Some(elements) = self.stack.pop() else {
+            return;
+        };
+        let value = (!elements.is_empty()).then_some(Value::Object(elements));
+        match (self.stack.last_mut(), value) {
+            (None, value) => self.result = value,
+            (Some(_), None) => {}
+            (Some(parent), Some(value)) => match parent.get_mut(name) {
+                Some(Value::Array(array)) => array.push(value),
+                Some(found) => *found = Value::Array(vec![found.take(), value]),
+                None => {
+                    parent.insert(name.into(), value);
+                }
+            },
+        }
+    }
+}
+
+/// the text of a converted value, JSON for values that aren't strings
+fn value_text(value: &Value) -> Cow<'_, str> {
+    match value {
+        Value::String(text) => Cow::Borrowed(text),
+        value => Cow::Owned(value.to_string()),
+    }
+}
+
+impl XmlToJson {
+    /// Parse XML string and drive `visitor` through all converted elements. This allows
+    /// custom outputs like CSV rows or indexes. The elements are traversed like in
+    /// `xml_to_json`, so names, key filters, stripped namespaces, element callbacks, type
+    /// overrides and the other options of single elements apply, and values that aren't
+    /// strings are passed as JSON. Options that shape the JSON, like the null policy, plural
+    /// names, arrays, key renames or templates, don't apply, and includes aren't resolved.
+    pub fn visit(
+        &self,
+        xml: &str,
+        visitor: &mut impl Visitor,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut ctx = Context {
+            deadline: self.deadline_from_now(),
+            ..Default::default()
+        };
+        let xml = self.preprocess(xml, &mut ctx)?;
+        let doc = roxmltree::Document::parse_with_options(&xml, self.parsing_options())?;
+        let root = doc.root_element();
+        let path_len = ctx.enter(&self.element_name(&root));
+        let name = match self.element_action(&root, &ctx) {
+            ElementAction::Rename(rename) => Cow::Owned(rename),
+            _ => self.element_name(&root),
+        };
+        self.visit_node(&root, &name, &mut ctx, visitor)?;
+        ctx.leave(path_len);
+        Ok(())
+    }
+
+    /// visit `node` after entering its path, `name` is its converted name
+    fn visit_node(
+        &self,
+        node: &roxmltree::Node,
+        name: &str,
+        ctx: &mut Context,
+        visitor: &mut impl Visitor,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        visitor.start_element(&ctx.path, name);
+        match self.element_start(node, ctx)? {
+            ElementStart::Done(Value::Object(entries)) => {
+                for (key, val) in &entries {
+                    visitor.text(key, &value_text(val));
+                }
+            }
+            ElementStart::Done(_) => {}
+            ElementStart::Entries(entries, text_key) => {
+                for (key, val) in &entries {
+                    match text_key == Some(key) {
+                        true => visitor.text(key, &value_text(val)),
+                        false => visitor.attribute(key, &value_text(val)),
+                    }
+                }
+                for child in self.converted_children(node) {
+                    let path_len = ctx.enter(&self.element_name(&child));
+                    let name = match self.element_action(&child, ctx) {
+                        ElementAction::Skip => None,
+                        ElementAction::Rename(rename) => Some(Cow::Owned(rename)),
+                        _ => Some(self.element_name(&child)),
+                    };
+                    if let Some(name) = name {
+                        self.visit_node(&child, &name, ctx, visitor)?;
+                    }
+                    ctx.leave(path_len);
+                }
+            }
+        }
+        visitor.end_element(name);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_visit() {
+    struct PathCollector(Vec<String>);
+    impl Visitor for PathCollector {
+        fn start_element(&mut self, path: &str, _: &str) {
+            self.0.push(path.into());
+        }
+        fn attribute(&mut self, _: &str, _: &str) {}
+        fn text(&mut self, _: &str, _: &str) {}
+        fn end_element(&mut self, _: &str) {}
+    }
+
+    let xml =
+        "<a><b href=\"#self\">simple</b><b><c class=\"my_class\"><d>D</d><d>1</d></c></b></a>";
+    let parser = XmlToJson::default();
+    let mut visitor = JsonVisitor::default();
+    parser.visit(xml, &mut visitor).unwrap();
+    assert_eq!(visitor.into_value(), parser.xml_to_json(xml).unwrap());
+
+    let mut collector = PathCollector(Vec::new());
+    parser.visit(xml, &mut collector).unwrap();
+    assert_eq!(
+        collector.0,
+        vec!["/a", "/a/b", "/a/b", "/a/b/c", "/a/b/c/d", "/a/b/c/d"]
+    );
+
+    let xml = r#"<a xmlns:n="urn:n"><n:b n:x="1">2</n:b><secret/><c/></a>"#;
+    let mut types = std::collections::HashMap::new();
+    types.insert("/a/n:b".to_string(), crate::JsonType::Number);
+    let parser = XmlToJson::default()
+        .with_namespace_mode(crate::NamespaceMode::Prefix)
+        .with_key_filter("secret", crate::FilterAction::Drop)
+        .with_type_overrides(types)
+        .with_element_callback(|_, name, _| match name {
+            "c" => ElementAction::Rename("d".into()),
+            _ => ElementAction::Continue,
+        });
+    let mut visitor = JsonVisitor::default();
+    parser.visit(xml, &mut visitor).unwrap();
+    assert_eq!(
+        visitor.into_value(),
+        serde_json::json!({ "n:b": { "@n:x": "1", "#text": "2" } })
+    );
+    let mut collector = PathCollector(Vec::new());
+    parser.visit(xml, &mut collector).unwrap();
+    assert_eq!(collector.0, vec!["/a", "/a/n:b", "/a/c"]);
+}
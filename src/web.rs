@@ -0,0 +1,315 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+//! XML body extractor and responder for web services. The features `axum` and `actix-web`
+//! implement the extractor and responder traits of these frameworks, other frameworks can
+//! pass the content type and body to `XmlJson::from_body` and map the rejection to its
+//! `status`.
+
+use crate::XmlToJson;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// An XML request or response body that is converted from or to `T` via JSON
+#[derive(Clone, Debug, PartialEq)]
+pub struct XmlJson<T = Value>(pub T);
+
+/// Why an XML request body was rejected
+#[derive(Clone, Debug, PartialEq)]
+pub enum XmlJsonRejection {
+    /// the content type is missing or isn't XML
+    UnsupportedContentType(Option<String>),
+    /// the body isn't well-formed XML or can't be converted with the configuration
+    InvalidXml(String),
+    /// the converted JSON doesn't match `T`
+    InvalidData(String),
+    /// the web framework couldn't read the body, with its status code
+    Body(u16, String),
+}
+
+impl XmlJsonRejection {
+    /// the HTTP status code of the response
+    pub fn status(&self) -> u16 {
+        match self {
+            XmlJsonRejection::UnsupportedContentType(_) => 415,
+            XmlJsonRejection::InvalidXml(_) => 400,
+            XmlJsonRejection::InvalidData(_) => 422,
+            XmlJsonRejection::Body(status, _) => *status,
+        }
+    }
+}
+
+impl fmt::Display for XmlJsonRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XmlJsonRejection::UnsupportedContentType(Some(content_type)) => {
+                write!(f, "expected an XML content type, got '{content_type}'")
+            }
+            XmlJsonRejection::UnsupportedContentType(None) => {
+                write!(f, "expected an XML content type")
+            }
+            XmlJsonRejection::InvalidXml(err) => write!(f, "invalid XML body: {err}"),
+            XmlJsonRejection::InvalidData(err) => write!(f, "unexpected XML content: {err}"),
+            XmlJsonRejection::Body(_, err) => write!(f, "failed to read the body: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for XmlJsonRejection {}
+
+/// `application/xml`, `text/xml` or a `+xml` type like `application/atom+xml`, with parameters
+fn is_xml_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    let mime = mime.to_ascii_lowercase();
+    mime == "application/xml" || mime == "text/xml" || mime.ends_with("+xml")
+}
+
+impl<T: DeserializeOwned> XmlJson<T> {
+    /// convert a request body with `parser`, usually shared in the application state,
+    /// and deserialize it into `T`
+    pub fn from_body(
+        parser: &XmlToJson,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<Self, XmlJsonRejection> {
+        match content_type {
+            Some(content_type) if is_xml_content_type(content_type) => {}
+            other => {
+                return Err(XmlJsonRejection::UnsupportedContentType(
+                    other.map(String::from),
+                ))
+            }
+        }
+        let value = parser
+            .xml_to_json_bytes(body)
+            .map_err(|err| XmlJsonRejection::InvalidXml(err.to_string()))?;
+        let data = serde_json::from_value(value)
+            .map_err(|err| XmlJsonRejection::InvalidData(err.to_string()))?;
+        Ok(XmlJson(data))
+    }
+}
+
+impl<T: Serialize> XmlJson<T> {
+    /// the content type of responses
+    pub const CONTENT_TYPE: &'static str = "application/xml; charset=utf-8";
+
+    /// write `T` as XML response body with root element `root_name`, see
+    /// `XmlToJson::json_to_xml_pretty`
+    pub fn to_body(
+        &self,
+        parser: &XmlToJson,
+        root_name: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let value = serde_json::to_value(&self.0)?;
+        parser.json_to_xml_pretty(&value, root_name, 0)
+    }
+}
+
+/// Extracts an XML request body with the `Arc<XmlToJson>` of the application state
+#[cfg(feature = "axum")]
+impl<T, S> axum::extract::FromRequest<S> for XmlJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+    std::sync::Arc<XmlToJson>: axum::extract::FromRef<S>,
+{
+    type Rejection = XmlJsonRejection;
+
+    async fn from_request(req: axum::extract::Request, state: &S) -> Result<Self, Self::Rejection> {
+        use axum::extract::FromRef;
+
+        let content_type = req
+            .headers()
+            .get(axum::http::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(String::from);
+        let body = axum::body::Bytes::from_request(req, state)
+            .await
+            .map_err(|err| XmlJsonRejection::Body(err.status().as_u16(), err.body_text()))?;
+        let parser = std::sync::Arc::<XmlToJson>::from_ref(state);
+        Self::from_body(&parser, content_type.as_deref(), &body)
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for XmlJsonRejection {
+    fn into_response(self) -> axum::response::Response {
+        let status = axum::http::StatusCode::from_u16(self.status())
+            .unwrap_or(axum::http::StatusCode::BAD_REQUEST);
+        (status, self.to_string()).into_response()
+    }
+}
+
+/// Writes `T` with the default options, `T` has to contain the root element like the
+/// output of `with_root`. Use `to_body` for other options.
+#[cfg(feature = "axum")]
+impl<T: Serialize> axum::response::IntoResponse for XmlJson<T> {
+    fn into_response(self) -> axum::response::Response {
+        use axum::http::{header, StatusCode};
+
+        match self.to_body(&XmlToJson::default(), "") {
+            Ok(body) => ([(header::CONTENT_TYPE, Self::CONTENT_TYPE)], body).into_response(),
+            Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        }
+    }
+}
+
+/// Extracts an XML request body with the `web::Data<XmlToJson>` of the application, or
+/// with the default options if there is none
+#[cfg(feature = "actix-web")]
+impl<T: DeserializeOwned + 'static> actix_web::FromRequest for XmlJson<T> {
+    type Error = XmlJsonRejection;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(
+        req: &actix_web::HttpRequest,
+        payload: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        let req = req.clone();
+        let body = actix_web::web::Bytes::from_request(&req, payload);
+        Box::pin(async move {
+            let body = body.await.map_err(|err| {
+                let response = err.as_response_error();
+                XmlJsonRejection::Body(response.status_code().as_u16(), response.to_string())
+            })?;
+            let content_type = req
+                .headers()
+                .get(actix_web::http::header::CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok());
+            match req.app_data::<actix_web::web::Data<XmlToJson>>() {
+                Some(parser) => Self::from_body(parser, content_type, &body),
+                None => Self::from_body(&XmlToJson::default(), content_type, &body),
+            }
+        })
+    }
+}
+
+#[cfg(feature = "actix-web")]
+impl actix_web::ResponseError for XmlJsonRejection {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        actix_web::http::StatusCode::from_u16(self.status())
+            .unwrap_or(actix_web::http::StatusCode::BAD_REQUEST)
+    }
+}
+
+/// Writes `T` with the `web::Data<XmlToJson>` of the application or the default options,
+/// `T` has to contain the root element like the output of `with_root`
+#[cfg(feature = "actix-web")]
+impl<T: Serialize> actix_web::Responder for XmlJson<T> {
+    type Body = actix_web::body::BoxBody;
+
+    fn respond_to(self, req: &actix_web::HttpRequest) -> actix_web::HttpResponse {
+        let body = match req.app_data::<actix_web::web::Data<XmlToJson>>() {
+            Some(parser) => self.to_body(parser, ""),
+            None => self.to_body(&XmlToJson::default(), ""),
+        };
+        match body {
+            Ok(body) => actix_web::HttpResponse::Ok()
+                .content_type(Self::CONTENT_TYPE)
+                .body(body),
+            Err(err) => actix_web::HttpResponse::InternalServerError().body(err.to_string()),
+        }
+    }
+}
+
+/// polls `future` until it is ready, the extractors in the tests don't wait for IO
+#[cfg(all(test, any(feature = "axum", feature = "actix-web")))]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn test_xml_json_body() {
+    use serde_json::json;
+
+    let parser = XmlToJson::default()
+        .with_flatten_text_arrays()
+        .with_inline_simple_children(crate::InlineConflict::Mixed);
+    let body = b"<order><id>5</id><item>a</item><item>b</item></order>";
+    let XmlJson(order) =
+        XmlJson::<Value>::from_body(&parser, Some("application/xml; charset=utf-8"), body).unwrap();
+    assert_eq!(order, json!({ "id": "5", "item": ["a", "b"] }));
+    let rejection = XmlJson::<Value>::from_body(&parser, Some("application/json"), body);
+    assert_eq!(rejection.unwrap_err().status(), 415);
+    let rejection = XmlJson::<Value>::from_body(&parser, Some("text/xml"), b"<order>");
+    assert_eq!(rejection.unwrap_err().status(), 400);
+    let rejection = XmlJson::<Vec<String>>::from_body(&parser, Some("text/xml"), body);
+    assert_eq!(rejection.unwrap_err().status(), 422);
+    let response = XmlJson(json!({ "id": "5" }))
+        .to_body(&parser, "order")
+        .unwrap();
+    assert!(response.starts_with("<order>") && response.contains("<id>5</id>"));
+}
+
+#[cfg(feature = "axum")]
+#[test]
+fn test_axum_xml_json() {
+    use axum::extract::FromRequest;
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use std::sync::Arc;
+
+    let state = Arc::new(XmlToJson::default().with_flatten_text_arrays());
+    let request = |content_type: &str, body: &'static str| {
+        axum::extract::Request::builder()
+            .header("content-type", content_type)
+            .body(axum::body::Body::from(body))
+            .unwrap()
+    };
+    let req = request(
+        "application/xml",
+        "<order><item>a</item><item>b</item></order>",
+    );
+    let XmlJson(order) = block_on(XmlJson::<Value>::from_request(req, &state)).unwrap();
+    assert_eq!(order, serde_json::json!({ "item": ["a", "b"] }));
+    let req = request("application/json", "<order/>");
+    let rejection = block_on(XmlJson::<Value>::from_request(req, &state)).unwrap_err();
+    assert_eq!(
+        rejection.into_response().status(),
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    );
+    let response = XmlJson(serde_json::json!({ "order": { "id": "5" } })).into_response();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        XmlJson::<Value>::CONTENT_TYPE
+    );
+}
+
+#[cfg(feature = "actix-web")]
+#[test]
+fn test_actix_xml_json() {
+    use actix_web::http::StatusCode;
+    use actix_web::{test::TestRequest, web::Data, FromRequest, Responder, ResponseError};
+
+    let parser = Data::new(XmlToJson::default().with_flatten_text_arrays());
+    let (req, mut payload) = TestRequest::default()
+        .insert_header(("content-type", "text/xml"))
+        .app_data(parser.clone())
+        .set_payload("<order><item>a</item><item>b</item></order>")
+        .to_http_parts();
+    let XmlJson(order) = block_on(XmlJson::<Value>::from_request(&req, &mut payload)).unwrap();
+    assert_eq!(order, serde_json::json!({ "item": ["a", "b"] }));
+    let (req, mut payload) = TestRequest::default()
+        .insert_header(("content-type", "text/xml"))
+        .set_payload("<order>")
+        .to_http_parts();
+    let rejection = block_on(XmlJson::<Value>::from_request(&req, &mut payload)).unwrap_err();
+    assert_eq!(rejection.status_code(), StatusCode::BAD_REQUEST);
+    let response = XmlJson(serde_json::json!({ "order": { "id": "5" } })).respond_to(&req);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        XmlJson::<Value>::CONTENT_TYPE
+    );
+}
@@ -0,0 +1,190 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+use crate::XmlToJson;
+use serde_json::{Map, Value};
+
+pub(crate) fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\t' if attribute => escaped.push_str("&#9;"),
+            '\n' if attribute => escaped.push_str("&#10;"),
+            '\r' if attribute => escaped.push_str("&#13;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+impl XmlToJson {
+    /// sort attributes by this list when writing XML, other attributes follow in key order.
+    /// Names are given without attribute prefix. An order recorded by
+    /// `with_preserve_attribute_order` takes precedence.
+    pub fn with_attribute_order(mut self, names: &[&str]) -> Self {
+        self.attribute_order = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Convert a JSON value back into indented XML, using the configured attribute prefix,
+    /// text names and plural names, see `MappingSpec`. If `root_name` is empty, `value` has to contain the root element, like the
+    /// output of `with_root`. A declaration is written if a `#declaration` key exists or
+    /// `with_declaration` is set.
+    pub fn json_to_xml_pretty(
+        &self,
+        value: &Value,
+        root_name: &str,
+        indent: usize,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let declaration = value.get("#declaration").and_then(Value::as_object);
+        let (root_name, root) = if root_name.is_empty() {
+            let mut elements = value
+                .as_object()
+                .ok_or("value must be an object that contains the root element")?
+                .iter()
+                .filter(|(key, _)| !key.starts_with('#'));
+            match (elements.next(), elements.next()) {
+                (Some((name, root)), None) => (name.as_str(), root),
+                _ => return Err("value must contain exactly one root element".into()),
+            }
+        } else {
+            (root_name, value)
+        };
+        let mut xml = String::new();
+        if declaration.is_some() || self.with_declaration {
+            self.write_declaration(&mut xml, declaration.unwrap_or(&Map::new()));
+        }
+        self.write_element(&mut xml, root_name, root, indent, 0);
+        Ok(xml)
+    }
+
+    fn write_declaration(&self, xml: &mut String, declaration: &Map<String, Value>) {
+        let pseudo = |name: &str| declaration.get(name).and_then(Value::as_str);
+        xml.push_str("<?xml version=\"");
+        xml.push_str(pseudo("version").unwrap_or("1.0"));
+        xml.push('"');
+        for name in ["encoding", "standalone"] {
+            if let Some(val) = pseudo(name) {
+                xml.push_str(&format!(" {name}=\"{}\"", escape(val, true)));
+            }
+        }
+        xml.push_str("?>\n");
+        if let Some(doctype) = pseudo("doctype") {
+            xml.push_str(&format!("<!DOCTYPE {doctype}>\n"));
+        }
+    }
+
+    fn write_element(
+        &self,
+        xml: &mut String,
+        name: &str,
+        value: &Value,
+        indent: usize,
+        level: usize,
+    ) {
+        if let Value::Array(items) = value {
+            for item in items {
+                self.write_element(xml, name, item, indent, level);
+            }
+            return;
+        }
+        let text_name = self.text_name_for(name);
+        xml.push_str(&" ".repeat(indent * level));
+        xml.push('<');
+        xml.push_str(name);
+        let mut text = scalar_text(value);
+        let mut children = Vec::new();
+        if let Value::Object(map) = value {
+            let mut attributes = Vec::new();
+            for (key, val) in map {
+                if key == text_name {
+                    text = scalar_text(val);
+                } else if let Some(attribute) = key
+                    .strip_prefix(self.attribute_prefix.as_str())
+                    .filter(|_| !self.attribute_prefix.is_empty())
+                {
+                    attributes.push((attribute, scalar_text(val).unwrap_or_default()));
+                } else if !key.starts_with('#') {
+                    children.push((key, val));
+                }
+            }
+            let recorded: Vec<&str> = map
+                .get("#attr_order")
+                .and_then(Value::as_array)
+                .map(|names| names.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            attributes.sort_by_key(|(attribute, _)| {
+                let position = |names: &[&str]| {
+                    names
+                        .iter()
+                        .position(|name| name == attribute)
+                        .unwrap_or(names.len())
+                };
+                let configured: Vec<&str> =
+                    self.attribute_order.iter().map(String::as_str).collect();
+                (position(&recorded), position(&configured))
+            });
+            for (attribute, val) in attributes {
+                xml.push_str(&format!(" {attribute}=\"{}\"", escape(&val, true)));
+            }
+        }
+        match (text, children.is_empty()) {
+            (None, true) => xml.push_str("/>\n"),
+            (Some(text), true) => {
+                xml.push_str(&format!(">{}</{name}>\n", escape(&text, false)));
+            }
+            (text, false) => {
+                xml.push_str(">\n");
+                if let Some(text) = text {
+                    xml.push_str(&" ".repeat(indent * (level + 1)));
+                    xml.push_str(&escape(&text, false));
+                    xml.push('\n');
+                }
+                for (child_key, child) in children {
+                    let child_name = self.singular_name(child_key);
+                    self.write_element(xml, child_name, child, indent, level + 1);
+                }
+                xml.push_str(&format!("{}</{name}>\n", " ".repeat(indent * level)));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "roxmltree")]
+#[test]
+fn test_json_to_xml_pretty() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?><config><server port="80" name="a &amp; b"><host>x</host><alias>y</alias><alias>z</alias><empty/></server></config>"#;
+    let parser = XmlToJson::default()
+        .with_root()
+        .with_declaration()
+        .with_null_policy(crate::NullPolicy::Never)
+        .with_attribute_order(&["port"]);
+    let value = parser.xml_to_json(xml).unwrap();
+    assert_eq!(
+        parser.json_to_xml_pretty(&value, "", 2).unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<config>
+  <server port="80" name="a &amp; b">
+    <alias>y</alias>
+    <alias>z</alias>
+    <empty/>
+    <host>x</host>
+  </server>
+</config>
+"#
+    );
+}
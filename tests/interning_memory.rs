@@ -0,0 +1,68 @@
+//  XML to JSON rust library
+//  © Copyright 2024, by Marco Mengelkoch
+//  Licensed under MIT License, see License file for more details
+//  git clone https://github.com/marcomq/xml_to_json_rs
+
+//! Compares the memory of `xml_to_interned` and `xml_to_json` on a repetitive document,
+//! counted by the global allocator. This is a separate test binary, so no other test
+//! allocates at the same time.
+
+#![cfg(all(feature = "std", feature = "roxmltree"))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use xml_to_json_rs::XmlToJson;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// runs `convert` and returns its result, the peak and the retained bytes it allocated
+fn measure<T>(convert: impl FnOnce() -> T) -> (T, usize, usize) {
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let result = convert();
+    let peak = PEAK.load(Ordering::Relaxed) - start;
+    let retained = CURRENT.load(Ordering::Relaxed) - start;
+    (result, peak, retained)
+}
+
+#[test]
+fn test_interning_memory() {
+    let row = "<row status=\"OK\" kind=\"update\"><id>1</id></row>";
+    let xml = format!("<feed>{}</feed>", row.repeat(20_000));
+    let parser = XmlToJson::default();
+
+    let (value, json_peak, json_retained) = measure(|| parser.xml_to_json(&xml).unwrap());
+    drop(value);
+    let (interned, interned_peak, interned_retained) =
+        measure(|| parser.xml_to_interned(&xml).unwrap());
+    drop(interned);
+
+    println!("xml_to_json:     peak {json_peak:>9} bytes, retained {json_retained:>9} bytes");
+    println!(
+        "xml_to_interned: peak {interned_peak:>9} bytes, retained {interned_retained:>9} bytes"
+    );
+    assert!(interned_retained * 2 < json_retained);
+    assert!(interned_peak < json_peak);
+}